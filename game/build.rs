@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+fn main() {
+    convert_sprites();
+    convert_fonts();
+}
+
+/// Directory holding the sprite artwork, relative to the manifest
+const SPRITE_DIR: &str = "assets/sprites";
+
+/// Directory holding the glyph sheets of the bespoke fonts, relative to the manifest
+const FONT_DIR: &str = "assets/fonts";
+
+/// A decoded 1bpp bitmap, `true` = lit pixel
+struct Bitmap {
+    width: usize,
+    height: usize,
+    pixels: Vec<bool>,
+}
+
+/// Converts every PBM/PNG in `assets/sprites` into `ImageRaw<BinaryColor>` constants.
+///
+/// `ship.pbm` becomes a single frame sprite `SHIP`, while `explosion_0.pbm`,
+/// `explosion_1.pbm`, ... become the frames of an animated sprite `EXPLOSION`.
+/// Black (PBM `1`, or dark opaque PNG pixels) is lit on the OLED.
+fn convert_sprites() {
+    // Sprite name -> frames ordered by frame number
+    let mut sprites: BTreeMap<String, BTreeMap<u32, Bitmap>> = BTreeMap::new();
+    for (stem, bitmap) in read_bitmaps(SPRITE_DIR) {
+        let (name, frame) = match stem.rsplit_once('_') {
+            Some((name, frame)) if frame.parse::<u32>().is_ok() => (name, frame.parse().unwrap()),
+            _ => (stem.as_str(), 0),
+        };
+        sprites
+            .entry(name.to_uppercase().replace('-', "_"))
+            .or_default()
+            .insert(frame, bitmap);
+    }
+
+    let mut code = String::from("// Generated by build.rs from assets/sprites, do not edit\n");
+    for (name, frames) in &sprites {
+        let first = frames.values().next().unwrap();
+        for (number, frame) in frames {
+            if frame.width != first.width || frame.height != first.height {
+                panic!(
+                    "Frame {} of sprite {} has a different size than frame 0",
+                    number, name
+                );
+            }
+        }
+
+        writeln!(code, "pub const {}: Sprite = Sprite::new(&[", name).unwrap();
+        for frame in frames.values() {
+            writeln!(
+                code,
+                "    ImageRaw::new(&{:?}, {}),",
+                pack_rows(frame),
+                frame.width
+            )
+            .unwrap();
+        }
+        writeln!(code, "]);").unwrap();
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("sprites.rs"), code).unwrap();
+}
+
+/// Converts every PBM/PNG in `assets/fonts` into an `ImageRaw<BinaryColor>` glyph sheet.
+///
+/// `compact.pbm` becomes `COMPACT_GLYPHS`. Glyph size, order and spacing are defined
+/// next to the `MonoFont` in `src/fonts.rs`.
+fn convert_fonts() {
+    let mut code = String::from("// Generated by build.rs from assets/fonts, do not edit\n");
+    for (stem, bitmap) in read_bitmaps(FONT_DIR) {
+        writeln!(
+            code,
+            "pub const {}_GLYPHS: ImageRaw<BinaryColor> = ImageRaw::new(&{:?}, {});",
+            stem.to_uppercase().replace('-', "_"),
+            pack_rows(&bitmap),
+            bitmap.width
+        )
+        .unwrap();
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("fonts.rs"), code).unwrap();
+}
+
+/// Decodes every PBM/PNG in `dir` in file name order, with the file name stems
+fn read_bitmaps(dir: &str) -> Vec<(String, Bitmap)> {
+    println!("cargo:rerun-if-changed={}", dir);
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| entries.map(|entry| entry.unwrap().path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+
+    let mut bitmaps = Vec::new();
+    for path in paths {
+        let bitmap = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("pbm") => parse_pbm(&path),
+            Some("png") => parse_png(&path),
+            _ => continue,
+        };
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let stem = path.file_stem().unwrap().to_str().unwrap().to_string();
+        bitmaps.push((stem, bitmap));
+    }
+    bitmaps
+}
+
+/// Packs a bitmap into MSB-first rows padded to whole bytes, the layout `ImageRaw` expects
+fn pack_rows(bitmap: &Bitmap) -> Vec<u8> {
+    let bytes_per_row = bitmap.width.div_ceil(8);
+    let mut data = vec![0u8; bytes_per_row * bitmap.height];
+    for y in 0..bitmap.height {
+        for x in 0..bitmap.width {
+            if bitmap.pixels[y * bitmap.width + x] {
+                data[y * bytes_per_row + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+    data
+}
+
+/// Parses a plain (P1) or raw (P4) portable bitmap
+fn parse_pbm(path: &Path) -> Bitmap {
+    let data = std::fs::read(path).unwrap();
+
+    // Header tokens are whitespace separated, `#` starts a comment until the end of the line
+    let mut pos = 0;
+    let mut next_token = |data: &[u8]| -> String {
+        loop {
+            while pos < data.len() && data[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            if pos < data.len() && data[pos] == b'#' {
+                while pos < data.len() && data[pos] != b'\n' {
+                    pos += 1;
+                }
+            } else {
+                break;
+            }
+        }
+        let start = pos;
+        while pos < data.len() && !data[pos].is_ascii_whitespace() && data[pos] != b'#' {
+            pos += 1;
+        }
+        String::from_utf8_lossy(&data[start..pos]).into_owned()
+    };
+
+    let magic = next_token(&data);
+    let width: usize = next_token(&data).parse().expect("invalid PBM width");
+    let height: usize = next_token(&data).parse().expect("invalid PBM height");
+
+    let pixels = match magic.as_str() {
+        "P1" => {
+            // Plain format: one 0/1 digit per pixel, whitespace optional
+            let mut pixels = Vec::with_capacity(width * height);
+            let mut in_comment = false;
+            for &byte in &data[pos..] {
+                match byte {
+                    b'#' => in_comment = true,
+                    b'\n' => in_comment = false,
+                    b'0' | b'1' if !in_comment => pixels.push(byte == b'1'),
+                    _ => {}
+                }
+            }
+            pixels
+        }
+        "P4" => {
+            // Raw format: a single whitespace byte, then MSB-first rows padded to whole bytes
+            let bytes_per_row = width.div_ceil(8);
+            let raster = &data[pos + 1..];
+            let mut pixels = Vec::with_capacity(width * height);
+            for y in 0..height {
+                for x in 0..width {
+                    let byte = raster[y * bytes_per_row + x / 8];
+                    pixels.push(byte & (0x80 >> (x % 8)) != 0);
+                }
+            }
+            pixels
+        }
+        _ => panic!("{}: unsupported PBM format {}", path.display(), magic),
+    };
+
+    if pixels.len() < width * height {
+        panic!("{}: expected {} pixels", path.display(), width * height);
+    }
+
+    Bitmap {
+        width,
+        height,
+        pixels: pixels[..width * height].to_vec(),
+    }
+}
+
+/// Decodes a PNG, dark opaque pixels are lit
+fn parse_png(path: &Path) -> Bitmap {
+    let file = std::fs::File::open(path).unwrap();
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().unwrap();
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).unwrap();
+
+    let channels = info.color_type.samples();
+    let mut pixels = Vec::with_capacity(info.width as usize * info.height as usize);
+    for y in 0..info.height as usize {
+        let row = &buffer[y * info.line_size..];
+        for x in 0..info.width as usize {
+            let pixel = &row[x * channels..(x + 1) * channels];
+            let (luma, alpha) = match info.color_type {
+                png::ColorType::Grayscale => (pixel[0] as u32, 255),
+                png::ColorType::GrayscaleAlpha => (pixel[0] as u32, pixel[1]),
+                png::ColorType::Rgb => (
+                    (pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3,
+                    255,
+                ),
+                png::ColorType::Rgba => (
+                    (pixel[0] as u32 + pixel[1] as u32 + pixel[2] as u32) / 3,
+                    pixel[3],
+                ),
+                png::ColorType::Indexed => unreachable!("expanded by normalize_to_color8"),
+            };
+            pixels.push(alpha >= 128 && luma < 128);
+        }
+    }
+
+    Bitmap {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    }
+}
@@ -0,0 +1,368 @@
+use crate::collision::Mask;
+use crate::fixed::Fixed;
+use crate::rng::Rng;
+
+/// Width and height of an asteroid mask in pixels
+pub const MASK_SIZE: usize = 24;
+
+/// Mask row and column of the asteroid center
+const CENTER: i32 = MASK_SIZE as i32 / 2;
+
+/// Largest radius that fits in a mask, leaving room for the rocky outline
+pub const MAX_RADIUS: u32 = CENTER as u32 - 2;
+
+/// Radius of the largest asteroids that spawn
+pub const LARGE_RADIUS: u32 = 5;
+
+/// Radius of the smallest asteroids, which are destroyed outright when hit
+pub const SMALL_RADIUS: u32 = 3;
+
+/// Most asteroids in play at once, fragments included
+pub const MAX_ASTEROIDS: usize = 8;
+
+/// Free slots needed for an asteroid to spawn. The one left over after the spawn, together
+/// with the slot of the asteroid being shot, has room for both of its fragments.
+pub const SPAWN_RESERVE: usize = 2;
+
+/// Sideways speed the two fragments of a split asteroid gain in opposite directions, in
+/// pixels per frame
+const FRAGMENT_DRIFT: Fixed = Fixed::from_ratio(1, 2);
+
+/// Whether an asteroid may spawn with `len` of `capacity` slots taken
+pub fn can_spawn(len: usize, capacity: usize) -> bool {
+    len + SPAWN_RESERVE <= capacity
+}
+
+/// Radius of the two fragments an asteroid splits into when hit, one size down.
+/// `None` for the smallest asteroids.
+pub fn fragment_radius(radius: u32) -> Option<u32> {
+    (radius > SMALL_RADIUS).then(|| radius - 1)
+}
+
+/// Points for shooting an asteroid. Like the arcade game, smaller ones are harder to hit
+/// and score more, 1 for a large asteroid up to 3 for a small one.
+pub fn points(radius: u32) -> u32 {
+    (LARGE_RADIUS + 1).saturating_sub(radius).max(1)
+}
+
+pub struct Asteroid {
+    pub x: Fixed,
+    pub y: i32,
+    /// Sideways speed in pixels per frame, asteroids only drift once split
+    pub vx: Fixed,
+    pub radius: u32,
+    pub shape: AsteroidShape,
+}
+
+impl Asteroid {
+    /// The two fragments one size down a shot asteroid splits into, drifting apart.
+    /// `None` for the smallest asteroids, which are destroyed outright.
+    pub fn fragments(&self, rng: &mut Rng) -> Option<[Asteroid; 2]> {
+        let radius = fragment_radius(self.radius)?;
+        Some([-FRAGMENT_DRIFT, FRAGMENT_DRIFT].map(|drift| Asteroid {
+            x: self.x,
+            y: self.y,
+            vx: self.vx + drift,
+            radius,
+            shape: AsteroidShape::generate(radius, rng.next_u32()),
+        }))
+    }
+}
+
+/// Pre-rendered 1bpp bitmap of an asteroid, centered in a [`MASK_SIZE`] square.
+///
+/// The irregular outline is generated once when the asteroid spawns, so drawing it every
+/// frame is a plain copy of the lit pixels.
+#[derive(Debug, Clone)]
+pub struct AsteroidShape {
+    /// One bit per pixel, bit 0 is the leftmost column
+    rows: [u32; MASK_SIZE],
+}
+
+impl AsteroidShape {
+    /// Renders the outline and interior texture for a radius (clamped to [`MAX_RADIUS`])
+    /// and seed. The same pair always gives the same shape.
+    pub fn generate(radius: u32, seed: u32) -> Self {
+        let mut shape = Self {
+            rows: [0; MASK_SIZE],
+        };
+        let r = radius.clamp(1, MAX_RADIUS) as i32;
+
+        // Irregular circle using circle points with pseudo-random variations,
+        // Bresenham-like approach with 8 octants
+        let mut oct_x = r;
+        let mut oct_y = 0;
+        let mut decision = 1 - r;
+
+        while oct_x >= oct_y {
+            let points = [
+                (oct_x, oct_y),
+                (oct_y, oct_x),
+                (-oct_x, oct_y),
+                (-oct_y, oct_x),
+                (-oct_x, -oct_y),
+                (-oct_y, -oct_x),
+                (oct_x, -oct_y),
+                (oct_y, -oct_x),
+            ];
+
+            for (i, &(dx, dy)) in points.iter().enumerate() {
+                // Create pseudo-random variation for each point
+                let point_seed = seed
+                    .wrapping_add((dx + dy * 256 + i as i32 * 17) as u32)
+                    .wrapping_mul(1103515245)
+                    .wrapping_add(12345);
+                let variation = ((point_seed >> 16) % 3) as i32 - 1;
+
+                // Plus-shaped pixel cluster for a rocky look
+                let px = dx + variation;
+                let py = dy + variation;
+                for (cx, cy) in [(0, 0), (-1, 0), (1, 0), (0, -1), (0, 1)] {
+                    shape.set(px + cx, py + cy);
+                }
+            }
+
+            oct_y += 1;
+            if decision < 0 {
+                decision += 2 * oct_y + 1;
+            } else {
+                oct_x -= 1;
+                decision += 2 * (oct_y - oct_x) + 1;
+            }
+        }
+
+        // Fill interior with scattered pixels for texture
+        for i in 0..(r * 3) {
+            let pixel_seed = seed
+                .wrapping_add((i * 13) as u32)
+                .wrapping_mul(1103515245)
+                .wrapping_add(12345);
+            let offset_x = ((pixel_seed >> 8) % (r as u32 * 2)) as i32 - r;
+            let offset_y = ((pixel_seed >> 16) % (r as u32 * 2)) as i32 - r;
+
+            if offset_x * offset_x + offset_y * offset_y < (r - 1) * (r - 1) {
+                shape.set(offset_x, offset_y);
+            }
+        }
+
+        shape
+    }
+
+    /// Lights the pixel at an offset from the center
+    fn set(&mut self, dx: i32, dy: i32) {
+        let (col, row) = (CENTER + dx, CENTER + dy);
+        if (0..MASK_SIZE as i32).contains(&col) && (0..MASK_SIZE as i32).contains(&row) {
+            self.rows[row as usize] |= 1 << col;
+        }
+    }
+
+    /// Collision mask of the shape centered on `x`, `y`, matching what [`Self::draw`] draws
+    pub fn mask(&self, x: i32, y: i32) -> Mask<'_> {
+        Mask {
+            x: x - CENTER,
+            y: y - CENTER,
+            width: MASK_SIZE as u32,
+            rows: &self.rows,
+        }
+    }
+
+    /// Calls `set_pixel` for every lit pixel with the shape centered on `x`, `y`.
+    /// Pixels left of or above the screen are skipped.
+    pub fn draw(&self, x: i32, y: i32, mut set_pixel: impl FnMut(u32, u32)) {
+        for (row, &bits) in self.rows.iter().enumerate() {
+            let py = y + row as i32 - CENTER;
+            if py < 0 {
+                continue;
+            }
+            let mut bits = bits;
+            while bits != 0 {
+                let col = bits.trailing_zeros() as i32;
+                bits &= bits - 1;
+                let px = x + col - CENTER;
+                if px >= 0 {
+                    set_pixel(px as u32, py as u32);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::hint::black_box;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::framebuffer::{FrameBuffer, PanelMirror};
+
+    /// The per-frame plotting the masks replaced, kept as the reference they must match
+    fn plot_per_pixel(x: i32, y: i32, radius: u32, seed: u32, mut set_pixel: impl FnMut(i32, i32)) {
+        let r = radius as i32;
+        let mut oct_x = r;
+        let mut oct_y = 0;
+        let mut decision = 1 - r;
+
+        while oct_x >= oct_y {
+            let points = [
+                (oct_x, oct_y),
+                (oct_y, oct_x),
+                (-oct_x, oct_y),
+                (-oct_y, oct_x),
+                (-oct_x, -oct_y),
+                (-oct_y, -oct_x),
+                (oct_x, -oct_y),
+                (oct_y, -oct_x),
+            ];
+            for (i, &(dx, dy)) in points.iter().enumerate() {
+                let point_seed = seed
+                    .wrapping_add((dx + dy * 256 + i as i32 * 17) as u32)
+                    .wrapping_mul(1103515245)
+                    .wrapping_add(12345);
+                let variation = ((point_seed >> 16) % 3) as i32 - 1;
+                let (px, py) = (x + dx + variation, y + dy + variation);
+                for pdx in -1..=1 {
+                    for pdy in -1..=1 {
+                        if pdx * pdx + pdy * pdy <= 1 {
+                            set_pixel(px + pdx, py + pdy);
+                        }
+                    }
+                }
+            }
+
+            oct_y += 1;
+            if decision < 0 {
+                decision += 2 * oct_y + 1;
+            } else {
+                oct_x -= 1;
+                decision += 2 * (oct_y - oct_x) + 1;
+            }
+        }
+
+        for i in 0..(r * 3) {
+            let pixel_seed = seed
+                .wrapping_add((i * 13) as u32)
+                .wrapping_mul(1103515245)
+                .wrapping_add(12345);
+            let offset_x = ((pixel_seed >> 8) % (r as u32 * 2)) as i32 - r;
+            let offset_y = ((pixel_seed >> 16) % (r as u32 * 2)) as i32 - r;
+            if offset_x * offset_x + offset_y * offset_y < (r - 1) * (r - 1) {
+                set_pixel(x + offset_x, y + offset_y);
+            }
+        }
+    }
+
+    /// Eight asteroids of every spawned size, as on a busy screen
+    fn field() -> [(i32, i32, u32, u32); 8] {
+        core::array::from_fn(|i| {
+            let i = i as i32;
+            let radius = SMALL_RADIUS + (i as u32 % 3);
+            (
+                10 + i * 15,
+                8 + i * 6,
+                radius,
+                0x9E37_79B9u32.wrapping_mul(i as u32 + 1),
+            )
+        })
+    }
+
+    #[test]
+    fn mask_matches_per_pixel_plotting() {
+        for (x, y, radius, seed) in field() {
+            let mut expected = FrameBuffer::new();
+            plot_per_pixel(x, y, radius, seed, |px, py| {
+                if px >= 0 && py >= 0 {
+                    expected.set_pixel(px as u32, py as u32, true);
+                }
+            });
+            let mut drawn = FrameBuffer::new();
+            AsteroidShape::generate(radius, seed)
+                .draw(x, y, |px, py| drawn.set_pixel(px, py, true));
+            let mut mirror = PanelMirror::new();
+            mirror.mark_shown(&expected);
+            let differs = mirror.dirty_spans(&drawn).next().is_some();
+            assert!(!differs, "asteroid at {x},{y} radius {radius}");
+        }
+    }
+
+    fn large_asteroid(seed: u32) -> Asteroid {
+        Asteroid {
+            x: Fixed::from_int(64),
+            y: 20,
+            vx: Fixed::ZERO,
+            radius: LARGE_RADIUS,
+            shape: AsteroidShape::generate(LARGE_RADIUS, seed),
+        }
+    }
+
+    #[test]
+    fn split_fits_after_spawning_up_to_the_guard() {
+        let mut rng = Rng::new(3);
+        let mut asteroids: heapless::Vec<Asteroid, MAX_ASTEROIDS> = heapless::Vec::new();
+        while can_spawn(asteroids.len(), asteroids.capacity()) {
+            assert!(asteroids.push(large_asteroid(rng.next_u32())).is_ok());
+        }
+        assert_eq!(asteroids.len(), MAX_ASTEROIDS + 1 - SPAWN_RESERVE);
+
+        // Shot asteroids leave the list before their fragments join it
+        let shot = asteroids.swap_remove(0);
+        let fragments = shot.fragments(&mut rng).unwrap();
+        for fragment in fragments {
+            assert!(asteroids.push(fragment).is_ok(), "no room for a fragment");
+        }
+    }
+
+    #[test]
+    fn fragments_drift_apart_one_size_down() {
+        let mut rng = Rng::new(9);
+        let [left, right] = large_asteroid(1).fragments(&mut rng).unwrap();
+        assert_eq!(
+            (left.radius, right.radius),
+            (LARGE_RADIUS - 1, LARGE_RADIUS - 1)
+        );
+        assert!(left.vx < Fixed::ZERO && right.vx > Fixed::ZERO);
+
+        let small = Asteroid {
+            radius: SMALL_RADIUS,
+            ..large_asteroid(2)
+        };
+        assert!(small.fragments(&mut rng).is_none());
+    }
+
+    /// Times both ways of drawing a full field of asteroids. Run on the host with
+    /// `cargo +stable test -p asteroids-game --release -- --ignored --nocapture
+    /// mask_drawing_speed`.
+    #[test]
+    #[ignore]
+    fn mask_drawing_speed() {
+        const FRAMES: u32 = 100_000;
+        let field = field();
+        let shapes = field.map(|(_, _, radius, seed)| AsteroidShape::generate(radius, seed));
+        let mut frame = FrameBuffer::new();
+
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            for &(x, y, radius, seed) in &field {
+                plot_per_pixel(x, y, radius, seed, |px, py| {
+                    if px >= 0 && py >= 0 {
+                        frame.set_pixel(px as u32, py as u32, true);
+                    }
+                });
+            }
+            black_box(&mut frame);
+        }
+        let per_pixel = start.elapsed() / FRAMES;
+
+        let start = Instant::now();
+        for _ in 0..FRAMES {
+            for ((x, y, ..), shape) in field.iter().zip(&shapes) {
+                shape.draw(*x, *y, |px, py| frame.set_pixel(px, py, true));
+            }
+            black_box(&mut frame);
+        }
+        let masks = start.elapsed() / FRAMES;
+
+        std::println!("per-pixel plotting: {per_pixel:?} per frame, masks: {masks:?} per frame");
+    }
+}
@@ -0,0 +1,177 @@
+//! Title screen attract mode: the pages it cycles through and the pilot that plays the demo.
+
+use crate::state::{AXIS_MAX, State};
+
+/// Page shown by the idle title screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Page {
+    Title,
+    /// The pilot plays a round
+    Demo,
+    Leaderboard,
+    Credits,
+}
+
+impl Page {
+    /// How long the page stays up before moving on
+    pub fn seconds(self) -> u64 {
+        match self {
+            Page::Title => 5,
+            Page::Demo => 20,
+            Page::Leaderboard => 5,
+            Page::Credits => 4,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Page::Title => Page::Demo,
+            Page::Demo => Page::Leaderboard,
+            Page::Leaderboard => Page::Credits,
+            Page::Credits => Page::Title,
+        }
+    }
+}
+
+/// An asteroid as the pilot sees it
+#[derive(Debug, Clone, Copy)]
+pub struct Threat {
+    pub x: i32,
+    pub y: i32,
+    pub radius: u32,
+}
+
+/// Rows above the ship where an asteroid in line is dodged instead of shot
+const DODGE_ROWS: i32 = 24;
+
+/// Extra columns kept between the ship and an asteroid being dodged
+const DODGE_MARGIN: i32 = 7;
+
+/// Columns off target that still count as lined up
+const AIM_SLACK: i32 = 1;
+
+/// Plays the demo round by producing the input a player would
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pilot {
+    /// Side of the last dodge, kept while the threat stays so the ship doesn't dither
+    dodge: i8,
+}
+
+impl Pilot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Input for this frame given the ship position, the columns from `min_x` to `max_x`
+    /// the ship can reach, and the asteroids in play. Asteroids about to reach the ship are
+    /// dodged, otherwise the ship lines up under the lowest asteroid and fires.
+    pub fn steer(
+        &mut self,
+        ship_x: i32,
+        ship_y: i32,
+        (min_x, max_x): (i32, i32),
+        threats: &[Threat],
+    ) -> State {
+        let mut state = State::new();
+
+        let incoming = threats
+            .iter()
+            .filter(|t| t.y < ship_y + 4 && ship_y - t.y < DODGE_ROWS)
+            .filter(|t| (t.x - ship_x).abs() < t.radius as i32 + DODGE_MARGIN)
+            .max_by_key(|t| t.y);
+
+        if let Some(threat) = incoming {
+            if self.dodge == 0 {
+                // Away from the asteroid, unless the wall keeps the ship from clearing it
+                let clear = threat.radius as i32 + DODGE_MARGIN;
+                let blocked = |side: i8| {
+                    if side > 0 {
+                        threat.x + clear > max_x
+                    } else {
+                        threat.x - clear < min_x
+                    }
+                };
+                let away = if ship_x >= threat.x { 1 } else { -1 };
+                self.dodge = if blocked(away) && !blocked(-away) {
+                    -away
+                } else {
+                    away
+                };
+            }
+            self.push(&mut state, self.dodge);
+            return state;
+        }
+        self.dodge = 0;
+
+        let target = threats
+            .iter()
+            .filter(|t| t.y < ship_y - DODGE_ROWS && t.y > 0)
+            .max_by_key(|t| t.y);
+        if let Some(target) = target {
+            let dx = target.x - ship_x;
+            if dx.abs() <= AIM_SLACK {
+                state.button_fire = true;
+            } else {
+                self.push(&mut state, dx.signum() as i8);
+            }
+        }
+        state
+    }
+
+    fn push(&self, state: &mut State, direction: i8) {
+        if direction < 0 {
+            state.button_left = true;
+        } else {
+            state.button_right = true;
+        }
+        state.push_axis(direction * AXIS_MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REACH: (i32, i32) = (8, 120);
+    const SHIP_Y: i32 = 58;
+
+    /// Direction the pilot moves with a single asteroid right above the ship
+    fn dodge(ship_x: i32, asteroid_x: i32) -> i8 {
+        let threat = Threat {
+            x: asteroid_x,
+            y: SHIP_Y - 10,
+            radius: 5,
+        };
+        let state = Pilot::new().steer(ship_x, SHIP_Y, REACH, &[threat]);
+        assert!(!state.button_fire);
+        state.button_right as i8 - state.button_left as i8
+    }
+
+    #[test]
+    fn dodges_away_from_the_asteroid() {
+        // More room on the far side of the asteroid doesn't pull the ship across it
+        assert_eq!(dodge(100, 96), 1);
+        assert_eq!(dodge(28, 32), -1);
+        assert_eq!(dodge(64, 64), 1);
+    }
+
+    #[test]
+    fn dodges_across_when_the_wall_is_in_the_way() {
+        assert_eq!(dodge(118, 114), -1);
+        assert_eq!(dodge(10, 14), 1);
+    }
+
+    #[test]
+    fn keeps_the_dodge_side_while_the_threat_stays() {
+        let mut pilot = Pilot::new();
+        let mut threat = Threat {
+            x: 96,
+            y: SHIP_Y - 10,
+            radius: 5,
+        };
+        assert!(pilot.steer(100, SHIP_Y, REACH, &[threat]).button_right);
+        // The asteroid drifts past the ship, which carries on instead of turning around
+        threat.x = 102;
+        assert!(pilot.steer(101, SHIP_Y, REACH, &[threat]).button_right);
+    }
+}
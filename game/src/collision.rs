@@ -0,0 +1,260 @@
+use embedded_graphics::{
+    image::{GetPixel, ImageRaw},
+    pixelcolor::BinaryColor,
+    prelude::*,
+};
+
+/// Widest mask in pixels, one row is a `u32`
+pub const MAX_MASK_WIDTH: u32 = 32;
+
+/// Tallest mask built from an image
+pub const MAX_MASK_HEIGHT: usize = 32;
+
+/// Mask rows of a bullet, the 6 pixel line drawn from its position downwards
+pub const BULLET_ROWS: [u32; 6] = [1; 6];
+
+/// A 1bpp shape placed on the playfield, for testing which drawn pixels overlap
+#[derive(Debug, Clone, Copy)]
+pub struct Mask<'a> {
+    /// Playfield position of the top-left mask pixel
+    pub x: i32,
+    pub y: i32,
+    /// Columns in use, at most [`MAX_MASK_WIDTH`]
+    pub width: u32,
+    /// One bit per pixel, bit 0 is the leftmost column
+    pub rows: &'a [u32],
+}
+
+impl Mask<'_> {
+    /// True if any lit pixel of `self` lands on a lit pixel of `other`
+    pub fn overlaps(&self, other: &Mask) -> bool {
+        // Bounding boxes first, most pairs are nowhere near each other
+        let left = self.x.max(other.x);
+        let right = (self.x + self.width as i32).min(other.x + other.width as i32);
+        let top = self.y.max(other.y);
+        let bottom = (self.y + self.rows.len() as i32).min(other.y + other.rows.len() as i32);
+        if left >= right || top >= bottom {
+            return false;
+        }
+
+        // Line both masks up on the left edge of the overlap and AND the rows
+        (top..bottom).any(|y| {
+            let a = self.rows[(y - self.y) as usize] >> (left - self.x);
+            let b = other.rows[(y - other.y) as usize] >> (left - other.x);
+            a & b != 0
+        })
+    }
+}
+
+/// Mask of a bullet drawn at `x`, `y`
+pub fn bullet_mask(x: i32, y: i32) -> Mask<'static> {
+    Mask {
+        x,
+        y,
+        width: 1,
+        rows: &BULLET_ROWS,
+    }
+}
+
+/// Mask of an image of `size` with [`image_rows`] `rows`, centered on `x`, `y` the way
+/// [`crate::sprite::Sprite::draw`] places it
+pub fn centered_mask(x: i32, y: i32, size: Size, rows: &[u32]) -> Mask<'_> {
+    Mask {
+        x: x - size.width as i32 / 2,
+        y: y - size.height as i32 / 2,
+        width: size.width,
+        rows,
+    }
+}
+
+/// Rows of lit pixels of an image in the [`Mask`] layout, cropped to the supported size
+pub fn image_rows(image: &ImageRaw<BinaryColor>) -> heapless::Vec<u32, MAX_MASK_HEIGHT> {
+    let size = image.size();
+    let width = size.width.min(MAX_MASK_WIDTH);
+    (0..size.height.min(MAX_MASK_HEIGHT as u32))
+        .map(|y| {
+            (0..width)
+                .filter(|&x| image.pixel(Point::new(x as i32, y as i32)) == Some(BinaryColor::On))
+                .fold(0, |row, x| row | 1 << x)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asteroid::{self, AsteroidShape};
+    use crate::framebuffer::{FrameBuffer, HEIGHT, WIDTH};
+    use crate::rng::Rng;
+    use crate::sprite;
+    use embedded_graphics::primitives::{Line, PrimitiveStyle};
+
+    /// A random shape of random size, about half of its pixels lit
+    fn random_rows(rng: &mut Rng) -> (u32, heapless::Vec<u32, MAX_MASK_HEIGHT>) {
+        let width = rng.range(1, MAX_MASK_WIDTH as i32 + 1) as u32;
+        let height = rng.range(1, MAX_MASK_HEIGHT as i32 + 1) as usize;
+        let columns = u32::MAX >> (MAX_MASK_WIDTH - width);
+        let rows = (0..height).map(|_| rng.next_u32() & columns).collect();
+        (width, rows)
+    }
+
+    fn is_lit(mask: &Mask, x: i32, y: i32) -> bool {
+        let (col, row) = (x - mask.x, y - mask.y);
+        (0..mask.width as i32).contains(&col)
+            && (0..mask.rows.len() as i32).contains(&row)
+            && mask.rows[row as usize] & 1 << col != 0
+    }
+
+    /// Checks every lit pixel of `a` against `b`
+    fn overlaps_brute_force(a: &Mask, b: &Mask) -> bool {
+        (0..a.rows.len() as i32).any(|row| {
+            (0..a.width as i32).any(|col| {
+                let (x, y) = (a.x + col, a.y + row);
+                is_lit(a, x, y) && is_lit(b, x, y)
+            })
+        })
+    }
+
+    #[test]
+    fn overlaps_matches_brute_force() {
+        let mut rng = Rng::new(0x5EED);
+        let mut hits = 0;
+        for _ in 0..5000 {
+            let (width_a, rows_a) = random_rows(&mut rng);
+            let (width_b, rows_b) = random_rows(&mut rng);
+            let a = Mask {
+                x: rng.range(-40, 40),
+                y: rng.range(-40, 40),
+                width: width_a,
+                rows: &rows_a,
+            };
+            let b = Mask {
+                x: rng.range(-40, 40),
+                y: rng.range(-40, 40),
+                width: width_b,
+                rows: &rows_b,
+            };
+            let expected = overlaps_brute_force(&a, &b);
+            assert_eq!(a.overlaps(&b), expected, "{a:?} {b:?}");
+            assert_eq!(b.overlaps(&a), expected, "{b:?} {a:?}");
+            hits += expected as u32;
+        }
+        // Both outcomes must come up for the comparison to mean anything
+        assert!(hits > 500 && hits < 4500, "{hits} overlaps");
+    }
+
+    #[test]
+    fn sparse_shapes_match_brute_force() {
+        let mut rng = Rng::new(7);
+        for _ in 0..5000 {
+            // Single pixels and thin lines, where off-by-one shifts show up
+            let rows_a = [1 << rng.range(0, 32)];
+            let rows_b: heapless::Vec<u32, 4> = (0..4).map(|_| 1 << rng.range(0, 32)).collect();
+            let a = Mask {
+                x: rng.range(-8, 8),
+                y: rng.range(-8, 8),
+                width: MAX_MASK_WIDTH,
+                rows: &rows_a,
+            };
+            let b = Mask {
+                x: rng.range(-8, 8),
+                y: rng.range(-8, 8),
+                width: MAX_MASK_WIDTH,
+                rows: &rows_b,
+            };
+            assert_eq!(a.overlaps(&b), overlaps_brute_force(&a, &b), "{a:?} {b:?}");
+        }
+    }
+
+    #[test]
+    fn touching_edges_do_not_overlap() {
+        let full = [u32::MAX; 4];
+        let a = Mask {
+            x: 0,
+            y: 0,
+            width: 32,
+            rows: &full,
+        };
+        let right = Mask { x: 32, ..a };
+        let below = Mask { y: 4, ..a };
+        assert!(!a.overlaps(&right));
+        assert!(!a.overlaps(&below));
+        assert!(a.overlaps(&Mask { x: 31, y: 3, ..a }));
+    }
+
+    /// Whether two things drawn into frames of their own light a pixel in common
+    fn frames_overlap(
+        draw_a: impl Fn(&mut FrameBuffer),
+        draw_b: impl Fn(&mut FrameBuffer),
+    ) -> bool {
+        let (mut a, mut b) = (FrameBuffer::new(), FrameBuffer::new());
+        draw_a(&mut a);
+        draw_b(&mut b);
+        (0..WIDTH as u32).any(|x| (0..HEIGHT as u32).any(|y| a.is_lit(x, y) && b.is_lit(x, y)))
+    }
+
+    // The three draw the way `App::render` does
+    fn draw_asteroid(frame: &mut FrameBuffer, shape: &AsteroidShape, x: i32, y: i32) {
+        shape.draw(x, y, |px, py| frame.set_pixel(px, py, true));
+    }
+
+    fn draw_bullet(frame: &mut FrameBuffer, x: i32, y: i32) {
+        Line::new(Point::new(x, y), Point::new(x, y + 5))
+            .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+            .draw(frame)
+            .unwrap();
+    }
+
+    fn draw_ship(frame: &mut FrameBuffer, x: i32, y: i32) {
+        sprite::SHIP.draw(0, Point::new(x, y), frame).unwrap();
+    }
+
+    /// A random asteroid somewhere it and everything around it stays on screen
+    fn random_asteroid(rng: &mut Rng) -> (AsteroidShape, i32, i32) {
+        let radius = rng.range(
+            asteroid::SMALL_RADIUS as i32,
+            asteroid::LARGE_RADIUS as i32 + 1,
+        ) as u32;
+        let shape = AsteroidShape::generate(radius, rng.next_u32());
+        let x = rng.range(16, WIDTH as i32 - 16);
+        (shape, x, HEIGHT as i32 / 2)
+    }
+
+    #[test]
+    fn bullet_mask_matches_drawn_frame() {
+        let mut rng = Rng::new(0xB011);
+        let mut hits = 0;
+        for _ in 0..2000 {
+            let (shape, ax, ay) = random_asteroid(&mut rng);
+            let (bx, by) = (ax + rng.range(-8, 9), ay + rng.range(-12, 8));
+            let expected = frames_overlap(
+                |frame| draw_asteroid(frame, &shape, ax, ay),
+                |frame| draw_bullet(frame, bx, by),
+            );
+            let hit = shape.mask(ax, ay).overlaps(&bullet_mask(bx, by));
+            assert_eq!(hit, expected, "asteroid at {ax},{ay}, bullet at {bx},{by}");
+            hits += expected as u32;
+        }
+        assert!(hits > 200 && hits < 1800, "{hits} hits");
+    }
+
+    #[test]
+    fn ship_mask_matches_drawn_frame() {
+        let ship_rows = image_rows(sprite::SHIP.frame(0));
+        let mut rng = Rng::new(0x5419);
+        let mut hits = 0;
+        for _ in 0..2000 {
+            let (shape, ax, ay) = random_asteroid(&mut rng);
+            let (sx, sy) = (ax + rng.range(-11, 12), ay + rng.range(-10, 11));
+            let expected = frames_overlap(
+                |frame| draw_asteroid(frame, &shape, ax, ay),
+                |frame| draw_ship(frame, sx, sy),
+            );
+            let ship = centered_mask(sx, sy, sprite::SHIP.size(), &ship_rows);
+            let hit = shape.mask(ax, ay).overlaps(&ship);
+            assert_eq!(hit, expected, "asteroid at {ax},{ay}, ship at {sx},{sy}");
+            hits += expected as u32;
+        }
+        assert!(hits > 200 && hits < 1800, "{hits} hits");
+    }
+}
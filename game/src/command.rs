@@ -0,0 +1,255 @@
+//! Parser for the line-based serial console commands.
+//!
+//! This module has no hardware dependencies so it can be exercised on the host.
+
+use crate::settings::{BurnInGuard, Contrast, FireMode, Hitbox, Language, Rotation, StarDensity};
+
+/// A console command, see [`HELP`] for the syntax of each
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Score,
+    ResetScores,
+    SetFps(u32),
+    SetFireMode(FireMode),
+    SetStars(StarDensity),
+    SetRotation(Rotation),
+    SetHitbox(Hitbox),
+    SetBurnIn(BurnInGuard),
+    SetContrast(Contrast),
+    SetLanguage(Language),
+    Sleep,
+    Seed(u32),
+    Stats,
+    Debug,
+}
+
+/// Reasons a command line can't be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    UnknownCommand,
+    UnknownSetting,
+    MissingArgument,
+    InvalidArgument,
+    TooManyArguments,
+}
+
+impl ParseError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            ParseError::Empty => "empty command",
+            ParseError::UnknownCommand => "unknown command, try `help`",
+            ParseError::UnknownSetting => "unknown setting, try `help`",
+            ParseError::MissingArgument => "missing argument",
+            ParseError::InvalidArgument => "invalid argument",
+            ParseError::TooManyArguments => "too many arguments",
+        }
+    }
+}
+
+/// Usage text printed by the `help` command
+pub const HELP: &str = "\
+help              show this text
+score             print the current and high score
+reset-scores      clear the saved high score
+set fps <n>       change the target frame rate (1-120)
+set fire <mode>   fire mode: auto or manual
+set stars <n>     starfield: off, low, medium or high
+set rotation <n>  playfield rotation: 0, 90, 180 or 270 (90/270 are portrait)
+set hitbox <mode> collisions: circle or pixel
+set burnin <mode> burn-in guard: on or off (frame shift and screensaver)
+set contrast <n>  brightness: low, medium, high or max
+set lang <code>   on-screen language: en or de
+sleep             enter sleep mode now
+seed <n>          restart the round with a fixed random seed
+stats             print runtime statistics
+debug             toggle the on-screen debug overlay (debug-overlay builds)";
+
+/// Parse a single command line, surrounding whitespace is ignored
+pub fn parse(line: &str) -> Result<Command, ParseError> {
+    let mut words = line.split_whitespace();
+    let name = words.next().ok_or(ParseError::Empty)?;
+
+    let command = match name {
+        "help" | "?" => Command::Help,
+        "score" => Command::Score,
+        "reset-scores" => Command::ResetScores,
+        "sleep" => Command::Sleep,
+        "stats" => Command::Stats,
+        "debug" => Command::Debug,
+        "seed" => Command::Seed(parse_number(words.next())?),
+        "set" => match words.next().ok_or(ParseError::MissingArgument)? {
+            "fps" => {
+                let fps = parse_number(words.next())?;
+                if !(1..=120).contains(&fps) {
+                    return Err(ParseError::InvalidArgument);
+                }
+                Command::SetFps(fps)
+            }
+            "fire" => match words.next().ok_or(ParseError::MissingArgument)? {
+                "auto" => Command::SetFireMode(FireMode::Auto),
+                "manual" => Command::SetFireMode(FireMode::Manual),
+                _ => return Err(ParseError::InvalidArgument),
+            },
+            "stars" => match words.next().ok_or(ParseError::MissingArgument)? {
+                "off" => Command::SetStars(StarDensity::Off),
+                "low" => Command::SetStars(StarDensity::Low),
+                "medium" => Command::SetStars(StarDensity::Medium),
+                "high" => Command::SetStars(StarDensity::High),
+                _ => return Err(ParseError::InvalidArgument),
+            },
+            "rotation" => match words.next().ok_or(ParseError::MissingArgument)? {
+                "0" => Command::SetRotation(Rotation::Deg0),
+                "90" => Command::SetRotation(Rotation::Deg90),
+                "180" => Command::SetRotation(Rotation::Deg180),
+                "270" => Command::SetRotation(Rotation::Deg270),
+                _ => return Err(ParseError::InvalidArgument),
+            },
+            "hitbox" => match words.next().ok_or(ParseError::MissingArgument)? {
+                "circle" => Command::SetHitbox(Hitbox::Circle),
+                "pixel" => Command::SetHitbox(Hitbox::Pixel),
+                _ => return Err(ParseError::InvalidArgument),
+            },
+            "burnin" => match words.next().ok_or(ParseError::MissingArgument)? {
+                "on" => Command::SetBurnIn(BurnInGuard::On),
+                "off" => Command::SetBurnIn(BurnInGuard::Off),
+                _ => return Err(ParseError::InvalidArgument),
+            },
+            "contrast" => match words.next().ok_or(ParseError::MissingArgument)? {
+                "low" => Command::SetContrast(Contrast::Low),
+                "medium" => Command::SetContrast(Contrast::Medium),
+                "high" => Command::SetContrast(Contrast::High),
+                "max" => Command::SetContrast(Contrast::Max),
+                _ => return Err(ParseError::InvalidArgument),
+            },
+            "lang" => match words.next().ok_or(ParseError::MissingArgument)? {
+                "en" => Command::SetLanguage(Language::English),
+                "de" => Command::SetLanguage(Language::German),
+                _ => return Err(ParseError::InvalidArgument),
+            },
+            _ => return Err(ParseError::UnknownSetting),
+        },
+        _ => return Err(ParseError::UnknownCommand),
+    };
+
+    if words.next().is_some() {
+        return Err(ParseError::TooManyArguments);
+    }
+    Ok(command)
+}
+
+fn parse_number(word: Option<&str>) -> Result<u32, ParseError> {
+    word.ok_or(ParseError::MissingArgument)?
+        .parse()
+        .map_err(|_| ParseError::InvalidArgument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_command() {
+        let cases = [
+            ("help", Command::Help),
+            ("?", Command::Help),
+            ("score", Command::Score),
+            ("reset-scores", Command::ResetScores),
+            ("sleep", Command::Sleep),
+            ("stats", Command::Stats),
+            ("debug", Command::Debug),
+            ("seed 42", Command::Seed(42)),
+            ("set fps 1", Command::SetFps(1)),
+            ("set fps 120", Command::SetFps(120)),
+            ("set fire auto", Command::SetFireMode(FireMode::Auto)),
+            ("set fire manual", Command::SetFireMode(FireMode::Manual)),
+            ("set stars off", Command::SetStars(StarDensity::Off)),
+            ("set stars low", Command::SetStars(StarDensity::Low)),
+            ("set stars medium", Command::SetStars(StarDensity::Medium)),
+            ("set stars high", Command::SetStars(StarDensity::High)),
+            ("set rotation 0", Command::SetRotation(Rotation::Deg0)),
+            ("set rotation 90", Command::SetRotation(Rotation::Deg90)),
+            ("set rotation 180", Command::SetRotation(Rotation::Deg180)),
+            ("set rotation 270", Command::SetRotation(Rotation::Deg270)),
+            ("set hitbox circle", Command::SetHitbox(Hitbox::Circle)),
+            ("set hitbox pixel", Command::SetHitbox(Hitbox::Pixel)),
+            ("set burnin on", Command::SetBurnIn(BurnInGuard::On)),
+            ("set burnin off", Command::SetBurnIn(BurnInGuard::Off)),
+            ("set contrast low", Command::SetContrast(Contrast::Low)),
+            (
+                "set contrast medium",
+                Command::SetContrast(Contrast::Medium),
+            ),
+            ("set contrast high", Command::SetContrast(Contrast::High)),
+            ("set contrast max", Command::SetContrast(Contrast::Max)),
+            ("set lang en", Command::SetLanguage(Language::English)),
+            ("set lang de", Command::SetLanguage(Language::German)),
+        ];
+        for (line, command) in cases {
+            assert_eq!(parse(line), Ok(command), "{line}");
+        }
+    }
+
+    #[test]
+    fn ignores_surrounding_whitespace() {
+        assert_eq!(parse("  set   fps\t60 \r"), Ok(Command::SetFps(60)));
+    }
+
+    #[test]
+    fn rejects_empty_lines() {
+        assert_eq!(parse(""), Err(ParseError::Empty));
+        assert_eq!(parse("   "), Err(ParseError::Empty));
+    }
+
+    #[test]
+    fn reports_missing_arguments() {
+        for line in [
+            "seed",
+            "set",
+            "set fps",
+            "set fire",
+            "set stars",
+            "set rotation",
+            "set hitbox",
+            "set burnin",
+            "set contrast",
+            "set lang",
+        ] {
+            assert_eq!(parse(line), Err(ParseError::MissingArgument), "{line}");
+        }
+    }
+
+    #[test]
+    fn reports_invalid_arguments() {
+        for line in [
+            "seed x",
+            "seed -1",
+            "set fps 0",
+            "set fps 121",
+            "set fps fast",
+            "set fire sometimes",
+            "set stars many",
+            "set rotation 45",
+            "set hitbox square",
+            "set burnin maybe",
+            "set contrast ultra",
+            "set lang fr",
+        ] {
+            assert_eq!(parse(line), Err(ParseError::InvalidArgument), "{line}");
+        }
+    }
+
+    #[test]
+    fn reports_unknown_settings_and_commands() {
+        assert_eq!(parse("set volume 3"), Err(ParseError::UnknownSetting));
+        assert_eq!(parse("jump"), Err(ParseError::UnknownCommand));
+        assert_eq!(parse("Help"), Err(ParseError::UnknownCommand));
+    }
+
+    #[test]
+    fn rejects_extra_arguments() {
+        assert_eq!(parse("score now"), Err(ParseError::TooManyArguments));
+        assert_eq!(parse("set fps 30 60"), Err(ParseError::TooManyArguments));
+    }
+}
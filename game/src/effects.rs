@@ -0,0 +1,290 @@
+//! Short visual effects triggered by game events: screen shake, an inverted flash, slow
+//! motion and transitions between scenes.
+//!
+//! Every effect counts down in frames, [`Effects::tick`] advances them all once per drawn
+//! frame. This module has no hardware dependencies so it can be exercised on the host.
+
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+
+/// Frames a scene transition takes
+pub const TRANSITION_FRAMES: u8 = 12;
+
+/// Directions a shake cycles through, scaled by its strength
+const SHAKE_PATTERN: [(i32, i32); 4] = [(1, 0), (-1, 1), (0, -1), (-1, 0)];
+
+/// How the next scene is uncovered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    /// From left to right
+    Wipe,
+    /// By a circle growing from the center
+    Iris,
+}
+
+/// The effects in progress
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Effects {
+    shake_frames: u8,
+    shake_strength: u8,
+    flash_frames: u8,
+    slow_frames: u16,
+    slow_factor: u8,
+    /// Frames since the game last moved in slow motion
+    slow_phase: u8,
+    /// Transition in progress and the frames it has run
+    transition: Option<(Transition, u8)>,
+}
+
+impl Effects {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shakes the screen for `frames` frames by up to `strength` pixels, easing off
+    /// towards the end. A shake already running keeps the longer and stronger of the two.
+    pub fn shake(&mut self, frames: u8, strength: u8) {
+        self.shake_frames = self.shake_frames.max(frames);
+        self.shake_strength = self.shake_strength.max(strength);
+    }
+
+    /// Offset of everything drawn this frame
+    pub fn shake_offset(&self) -> (i32, i32) {
+        if self.shake_frames == 0 {
+            return (0, 0);
+        }
+        let amplitude = self.shake_strength.min(self.shake_frames) as i32;
+        let (dx, dy) = SHAKE_PATTERN[self.shake_frames as usize % SHAKE_PATTERN.len()];
+        (dx * amplitude, dy * amplitude)
+    }
+
+    /// Inverts the whole panel for `frames` frames
+    pub fn flash(&mut self, frames: u8) {
+        self.flash_frames = self.flash_frames.max(frames);
+    }
+
+    pub fn is_inverted(&self) -> bool {
+        self.flash_frames > 0
+    }
+
+    /// Runs the game `factor` times slower for the next `frames` frames
+    pub fn slow_motion(&mut self, frames: u16, factor: u8) {
+        self.slow_frames = frames;
+        self.slow_factor = factor.max(1);
+        self.slow_phase = 0;
+    }
+
+    /// Whether the game should stand still this frame to run in slow motion
+    pub fn skips_update(&self) -> bool {
+        self.slow_frames > 0 && self.slow_phase > 0
+    }
+
+    /// Starts uncovering the scene drawn from now on, replacing a transition in progress
+    pub fn start_transition(&mut self, transition: Transition) {
+        self.transition = Some((transition, 0));
+    }
+
+    /// Whether the picture changes from frame to frame even if nothing else moves
+    pub fn is_animating(&self) -> bool {
+        self.shake_frames > 0 || self.transition.is_some()
+    }
+
+    /// Blanks the part of the frame the transition hasn't uncovered yet, call after
+    /// drawing the rest of the frame
+    pub fn draw_transition<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let Some((transition, frame)) = self.transition else {
+            return Ok(());
+        };
+        let bounds = target.bounding_box();
+        let (frame, frames) = (frame as u32, TRANSITION_FRAMES as u32);
+
+        match transition {
+            Transition::Wipe => {
+                let edge = bounds.size.width * frame / frames;
+                let covered = Rectangle::new(
+                    bounds.top_left + Point::new(edge as i32, 0),
+                    Size::new(bounds.size.width - edge, bounds.size.height),
+                );
+                target.fill_solid(&covered, BinaryColor::Off)
+            }
+            Transition::Iris => {
+                let center = bounds.center();
+                // Distance to the farthest corner, which the circle reaches on the last frame
+                let half = bounds.size / 2;
+                let corner = (half.width * half.width + half.height * half.height) as i32;
+                let full = isqrt(corner) + 1;
+                let radius = full * frame as i32 / frames as i32;
+                let outside = bounds.points().filter(|p| {
+                    let (dx, dy) = (p.x - center.x, p.y - center.y);
+                    dx * dx + dy * dy > radius * radius
+                });
+                target.draw_iter(outside.map(|p| Pixel(p, BinaryColor::Off)))
+            }
+        }
+    }
+
+    /// Advances every effect by one frame, call once per drawn frame
+    pub fn tick(&mut self) {
+        self.shake_frames = self.shake_frames.saturating_sub(1);
+        if self.shake_frames == 0 {
+            self.shake_strength = 0;
+        }
+        self.flash_frames = self.flash_frames.saturating_sub(1);
+        if self.slow_frames > 0 {
+            self.slow_frames -= 1;
+            self.slow_phase = (self.slow_phase + 1) % self.slow_factor;
+        }
+        if let Some((_, frame)) = &mut self.transition {
+            *frame += 1;
+            if *frame >= TRANSITION_FRAMES {
+                self.transition = None;
+            }
+        }
+    }
+}
+
+/// Integer square root, rounded down
+fn isqrt(n: i32) -> i32 {
+    let mut root = 0;
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::{DirtySpan, FrameBuffer, HEIGHT, PAGES, WIDTH};
+
+    fn is_lit(frame: &FrameBuffer, x: usize, y: usize) -> bool {
+        let span = DirtySpan {
+            page: (y / 8) as u8,
+            start: 0,
+            end: WIDTH as u8,
+        };
+        frame.span_bytes(&span)[x] & 1 << (y % 8) != 0
+    }
+
+    fn lit_count(frame: &FrameBuffer) -> u32 {
+        (0..PAGES)
+            .map(|page| {
+                let span = DirtySpan {
+                    page: page as u8,
+                    start: 0,
+                    end: WIDTH as u8,
+                };
+                frame
+                    .span_bytes(&span)
+                    .iter()
+                    .map(|b| b.count_ones())
+                    .sum::<u32>()
+            })
+            .sum()
+    }
+
+    /// A lit frame after the transition blanked what it hasn't uncovered yet
+    fn uncovered(effects: &Effects) -> FrameBuffer {
+        let mut frame = FrameBuffer::new();
+        frame.clear(BinaryColor::On).unwrap();
+        effects.draw_transition(&mut frame).unwrap();
+        frame
+    }
+
+    #[test]
+    fn wipe_uncovers_whole_columns_from_the_left() {
+        let mut effects = Effects::new();
+        effects.start_transition(Transition::Wipe);
+        for frame in 0..TRANSITION_FRAMES as usize {
+            let edge = WIDTH * frame / TRANSITION_FRAMES as usize;
+            let shown = uncovered(&effects);
+            for x in 0..WIDTH {
+                for y in 0..HEIGHT {
+                    assert_eq!(is_lit(&shown, x, y), x < edge, "frame {frame} at {x},{y}");
+                }
+            }
+            effects.tick();
+        }
+        assert!(!effects.is_animating());
+        assert_eq!(lit_count(&uncovered(&effects)), (WIDTH * HEIGHT) as u32);
+    }
+
+    #[test]
+    fn iris_grows_symmetrically_from_the_center() {
+        let mut effects = Effects::new();
+        effects.start_transition(Transition::Iris);
+        let mut last = 0;
+        for frame in 0..TRANSITION_FRAMES {
+            let shown = uncovered(&effects);
+            let lit = lit_count(&shown);
+            assert!(lit > last, "frame {frame}: {lit} after {last}");
+            last = lit;
+
+            // Mirrored around the center pixel, the last row and column have no mirror
+            let (cx, cy) = (WIDTH / 2 - 1, HEIGHT / 2 - 1);
+            for x in 0..=2 * cx {
+                for y in 0..=2 * cy {
+                    assert_eq!(is_lit(&shown, x, y), is_lit(&shown, 2 * cx - x, 2 * cy - y));
+                }
+            }
+            effects.tick();
+        }
+        assert!(last < (WIDTH * HEIGHT) as u32);
+        assert!(!effects.is_animating());
+    }
+
+    #[test]
+    fn shake_stays_within_strength_and_settles() {
+        let mut effects = Effects::new();
+        effects.shake(10, 2);
+        for _ in 0..10 {
+            let (dx, dy) = effects.shake_offset();
+            assert!(dx.abs() <= 2 && dy.abs() <= 2);
+            assert!(effects.is_animating());
+            effects.tick();
+        }
+        assert_eq!(effects.shake_offset(), (0, 0));
+        assert!(!effects.is_animating());
+    }
+
+    #[test]
+    fn shaken_frame_moves_pixels_by_the_offset() {
+        let mut effects = Effects::new();
+        effects.shake(10, 2);
+        let (dx, dy) = effects.shake_offset();
+        assert_ne!((dx, dy), (0, 0));
+        let mut frame = FrameBuffer::new();
+        frame.set_offset(dx, dy);
+        frame.set_pixel(40, 20, true);
+        let (x, y) = ((40 + dx) as usize, (20 + dy) as usize);
+        assert!(is_lit(&frame, x, y));
+        assert_eq!(lit_count(&frame), 1);
+    }
+
+    #[test]
+    fn flash_lasts_its_frames() {
+        let mut effects = Effects::new();
+        effects.flash(3);
+        let mut inverted = 0;
+        for _ in 0..10 {
+            inverted += effects.is_inverted() as u32;
+            effects.tick();
+        }
+        assert_eq!(inverted, 3);
+    }
+
+    #[test]
+    fn slow_motion_skips_updates_by_its_factor() {
+        let mut effects = Effects::new();
+        effects.slow_motion(30, 3);
+        let mut updates = 0;
+        for _ in 0..30 {
+            updates += !effects.skips_update() as u32;
+            effects.tick();
+        }
+        assert_eq!(updates, 10);
+        assert!(!effects.skips_update());
+    }
+}
@@ -0,0 +1,115 @@
+use core::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Signed 24.8 fixed-point number for sub-pixel positions and velocities.
+///
+/// Screen coordinates only need a few bits of integer range, so 8 fractional bits give
+/// 1/256 px precision with plenty of headroom and no floating point on the hot path.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    /// Number of fractional bits
+    pub const FRAC_BITS: u32 = 8;
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << Self::FRAC_BITS);
+
+    pub const fn from_int(value: i32) -> Self {
+        Fixed(value << Self::FRAC_BITS)
+    }
+
+    /// Creates a value from its raw representation in 1/256 units
+    pub const fn from_raw(raw: i32) -> Self {
+        Fixed(raw)
+    }
+
+    /// Creates the value `numerator / denominator`, e.g. `from_ratio(3, 8)` for 0.375
+    pub const fn from_ratio(numerator: i32, denominator: i32) -> Self {
+        Fixed((numerator << Self::FRAC_BITS) / denominator)
+    }
+
+    pub const fn raw(self) -> i32 {
+        self.0
+    }
+
+    /// Integer part, rounded towards negative infinity
+    pub const fn floor(self) -> i32 {
+        self.0 >> Self::FRAC_BITS
+    }
+
+    /// Nearest integer, halves round up
+    pub const fn round(self) -> i32 {
+        (self.0 + (1 << (Self::FRAC_BITS - 1))) >> Self::FRAC_BITS
+    }
+
+    pub const fn abs(self) -> Self {
+        Fixed(self.0.abs())
+    }
+
+    /// Scales by `numerator / denominator` without losing precision to an intermediate
+    pub const fn scale(self, numerator: i32, denominator: i32) -> Self {
+        Fixed((self.0 as i64 * numerator as i64 / denominator as i64) as i32)
+    }
+
+    /// Moves towards zero by `amount` without overshooting
+    pub fn approach_zero(self, amount: Fixed) -> Self {
+        if self.0 > amount.0 {
+            Fixed(self.0 - amount.0)
+        } else if self.0 < -amount.0 {
+            Fixed(self.0 + amount.0)
+        } else {
+            Fixed::ZERO
+        }
+    }
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 + rhs.0)
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        self.0 += rhs.0;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0 - rhs.0)
+    }
+}
+
+impl SubAssign for Fixed {
+    fn sub_assign(&mut self, rhs: Fixed) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(-self.0)
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Fixed) -> Fixed {
+        Fixed(((self.0 as i64 * rhs.0 as i64) >> Self::FRAC_BITS) as i32)
+    }
+}
+
+impl Mul<i32> for Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: i32) -> Fixed {
+        Fixed(self.0 * rhs)
+    }
+}
@@ -0,0 +1,133 @@
+//! Bespoke fonts: a compact 3x5 font for the HUD and large digits for banners.
+//!
+//! Both are plain [`MonoFont`]s, so any `Text` or `MonoTextStyle` can switch to them. The
+//! glyph sheets at the bottom of this module are generated by `build.rs` from the PBM/PNG
+//! files in `assets/fonts`, with the glyphs in the order given here.
+
+use embedded_graphics::{
+    image::ImageRaw,
+    mono_font::{
+        DecorationDimensions, MonoFont,
+        mapping::{GlyphMapping, StrGlyphMapping},
+    },
+    pixelcolor::BinaryColor,
+    prelude::*,
+};
+
+/// Glyphs of `assets/fonts/compact.pbm`, 16 per row
+const COMPACT_CHARS: &str = " !-./0123456789:?ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Glyphs of `assets/fonts/digits.pbm`
+const DIGIT_CHARS: &str = "0123456789 -:";
+
+/// Draws lowercase letters as capitals, there's no room for both at 3x5
+struct CompactMapping;
+
+impl GlyphMapping for CompactMapping {
+    fn index(&self, c: char) -> usize {
+        let c = c.to_ascii_uppercase();
+        COMPACT_CHARS
+            .find(c)
+            .or_else(|| COMPACT_CHARS.find('?'))
+            .unwrap_or(0)
+    }
+}
+
+/// 3x5 capitals, digits and a little punctuation, a line of HUD text takes 6 rows
+pub const FONT_3X5: MonoFont = MonoFont {
+    image: COMPACT_GLYPHS,
+    glyph_mapping: &CompactMapping,
+    character_size: Size::new(3, 5),
+    character_spacing: 1,
+    baseline: 4,
+    underline: DecorationDimensions::new(5, 1),
+    strikethrough: DecorationDimensions::new(2, 1),
+};
+
+/// 8x12 segment digits for banners, plus space, `-` and `:`. Anything else is blank.
+pub const FONT_DIGITS_8X12: MonoFont = MonoFont {
+    image: DIGITS_GLYPHS,
+    glyph_mapping: &StrGlyphMapping::new(DIGIT_CHARS, 10),
+    character_size: Size::new(8, 12),
+    character_spacing: 2,
+    baseline: 11,
+    underline: DecorationDimensions::new(13, 1),
+    strikethrough: DecorationDimensions::new(5, 2),
+};
+
+include!(concat!(env!("OUT_DIR"), "/fonts.rs"));
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::{mono_font::MonoTextStyle, text::Text};
+
+    use super::*;
+    use crate::framebuffer::{FrameBuffer, PanelMirror};
+
+    fn draw(text: &str, font: &MonoFont) -> FrameBuffer {
+        let mut frame = FrameBuffer::new();
+        let style = MonoTextStyle::new(font, BinaryColor::On);
+        Text::new(text, Point::new(0, font.baseline as i32), style)
+            .draw(&mut frame)
+            .unwrap();
+        frame
+    }
+
+    fn same_pixels(a: &FrameBuffer, b: &FrameBuffer) -> bool {
+        let mut mirror = PanelMirror::new();
+        mirror.mark_shown(a);
+        mirror.dirty_spans(b).next().is_none()
+    }
+
+    #[test]
+    fn compact_mapping_draws_lowercase_as_capitals() {
+        let mapping = FONT_3X5.glyph_mapping;
+        for (lower, upper) in ('a'..='z').zip('A'..='Z') {
+            assert_eq!(mapping.index(lower), mapping.index(upper));
+        }
+        // 3 columns a glyph plus 1 of spacing
+        assert_eq!(
+            crate::ui::text_width("ABCDEFGHIJKLMNOPQRSTUVWXYZ", &FONT_3X5),
+            104
+        );
+        let alphabet = draw("ABCDEFGHIJKLMNOPQRSTUVWXYZ", &FONT_3X5);
+        assert!(same_pixels(
+            &alphabet,
+            &draw("abcdefghijklmnopqrstuvwxyz", &FONT_3X5)
+        ));
+    }
+
+    #[test]
+    fn compact_mapping_falls_back_to_question_mark() {
+        let mapping = FONT_3X5.glyph_mapping;
+        for c in ['~', 'ö', '#', '€'] {
+            assert_eq!(mapping.index(c), COMPACT_CHARS.find('?').unwrap());
+        }
+        assert!(same_pixels(&draw("?", &FONT_3X5), &draw("~", &FONT_3X5)));
+    }
+
+    #[test]
+    fn compact_glyphs_match_the_sheet_order() {
+        for (i, c) in COMPACT_CHARS.chars().enumerate() {
+            assert_eq!(FONT_3X5.glyph_mapping.index(c), i);
+        }
+        // Blank space and a lit letter, to catch a sheet that's off by a glyph
+        assert!(same_pixels(&draw(" ", &FONT_3X5), &FrameBuffer::new()));
+        assert!(!same_pixels(&draw("A", &FONT_3X5), &FrameBuffer::new()));
+    }
+
+    #[test]
+    fn digits_cover_their_characters_and_blank_the_rest() {
+        for (i, c) in DIGIT_CHARS.chars().enumerate() {
+            assert_eq!(FONT_DIGITS_8X12.glyph_mapping.index(c), i);
+        }
+        let blank = FrameBuffer::new();
+        assert!(same_pixels(&draw("A", &FONT_DIGITS_8X12), &blank));
+        assert!(same_pixels(&draw(" ", &FONT_DIGITS_8X12), &blank));
+        for digit in '0'..='9' {
+            let mut text = [0; 4];
+            let drawn = draw(digit.encode_utf8(&mut text), &FONT_DIGITS_8X12);
+            assert!(!same_pixels(&drawn, &blank), "{digit}");
+        }
+    }
+}
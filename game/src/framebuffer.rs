@@ -0,0 +1,337 @@
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*};
+
+use crate::settings::Rotation;
+
+/// Panel width in pixels, before rotation
+pub const WIDTH: usize = 128;
+/// Panel height in pixels, before rotation
+#[cfg(not(feature = "ssd1306-128x32"))]
+pub const HEIGHT: usize = 64;
+#[cfg(feature = "ssd1306-128x32")]
+pub const HEIGHT: usize = 32;
+/// Rows of 8 vertical pixels, each stored as one byte per column like in SSD1306 memory
+pub const PAGES: usize = HEIGHT / 8;
+
+const BUFFER_SIZE: usize = WIDTH * PAGES;
+
+/// Columns of one page that changed since the last flush, `end` is exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtySpan {
+    pub page: u8,
+    pub start: u8,
+    pub end: u8,
+}
+
+impl DirtySpan {
+    /// Bytes needed to send this span to the panel
+    pub fn len(&self) -> usize {
+        (self.end - self.start) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+}
+
+/// Frame buffer in the SSD1306 page layout, drawn to with embedded-graphics.
+///
+/// Drawing happens in rotated coordinates, so [`OriginDimensions::size`] swaps width and
+/// height in portrait rotations.
+#[derive(Clone)]
+pub struct FrameBuffer {
+    buffer: [u8; BUFFER_SIZE],
+    rotation: Rotation,
+    /// Added to every drawn pixel, in drawing coordinates
+    offset: (i32, i32),
+}
+
+impl Default for FrameBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameBuffer {
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; BUFFER_SIZE],
+            rotation: Rotation::Deg0,
+            offset: (0, 0),
+        }
+    }
+
+    pub fn rotation(&self) -> Rotation {
+        self.rotation
+    }
+
+    /// Changes how drawing coordinates map to the panel, the contents are left as they are
+    pub fn set_rotation(&mut self, rotation: Rotation) {
+        self.rotation = rotation;
+    }
+
+    /// Moves everything drawn afterwards by `dx`, `dy` pixels, e.g. to shift the whole
+    /// frame against burn-in. Pixels moved off the panel are dropped.
+    pub fn set_offset(&mut self, dx: i32, dy: i32) {
+        self.offset = (dx, dy);
+    }
+
+    /// Turns a pixel on or off, coordinates outside the rotated panel are ignored
+    pub fn set_pixel(&mut self, x: u32, y: u32, on: bool) {
+        self.put(x as i32, y as i32, on);
+    }
+
+    /// Whether a pixel is lit, in the same coordinates as drawing. Pixels outside the
+    /// rotated panel are never lit.
+    pub fn is_lit(&self, x: u32, y: u32) -> bool {
+        self.locate(x as i32, y as i32)
+            .is_some_and(|(index, bit)| self.buffer[index] & bit != 0)
+    }
+
+    fn put(&mut self, x: i32, y: i32, on: bool) {
+        let Some((index, bit)) = self.locate(x, y) else {
+            return;
+        };
+        if on {
+            self.buffer[index] |= bit;
+        } else {
+            self.buffer[index] &= !bit;
+        }
+    }
+
+    /// Buffer byte and bit of a pixel in drawing coordinates, if it's on the panel
+    fn locate(&self, x: i32, y: i32) -> Option<(usize, u8)> {
+        let (x, y) = (x + self.offset.0, y + self.offset.1);
+        let size = self.size();
+        if x < 0 || y < 0 || x >= size.width as i32 || y >= size.height as i32 {
+            return None;
+        }
+        let (x, y) = (x as usize, y as usize);
+        let (x, y) = match self.rotation {
+            Rotation::Deg0 => (x, y),
+            Rotation::Deg90 => (WIDTH - 1 - y, x),
+            Rotation::Deg180 => (WIDTH - 1 - x, HEIGHT - 1 - y),
+            Rotation::Deg270 => (y, HEIGHT - 1 - x),
+        };
+        Some((y / 8 * WIDTH + x, 1 << (y % 8)))
+    }
+
+    /// Page memory covered by a span, in the order the panel expects it
+    pub fn span_bytes(&self, span: &DirtySpan) -> &[u8] {
+        let offset = span.page as usize * WIDTH;
+        &self.buffer[offset + span.start as usize..offset + span.end as usize]
+    }
+}
+
+/// Copy of what the panel currently shows, to find the parts of a new frame that changed.
+///
+/// Each flush only needs to send the column range of every page that differs from the
+/// previous frame, instead of the whole 1 KiB buffer.
+pub struct PanelMirror {
+    shown: [u8; BUFFER_SIZE],
+    /// Set while the panel contents are unknown, forcing the next flush to send everything
+    stale: bool,
+}
+
+impl Default for PanelMirror {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PanelMirror {
+    pub const fn new() -> Self {
+        Self {
+            shown: [0; BUFFER_SIZE],
+            stale: true,
+        }
+    }
+
+    /// Forces the next flush to send the whole frame, e.g. after the panel was reset
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Changed column range of every page where `frame` differs from what the panel shows
+    pub fn dirty_spans<'a>(
+        &'a self,
+        frame: &'a FrameBuffer,
+    ) -> impl Iterator<Item = DirtySpan> + 'a {
+        (0..PAGES).filter_map(move |page| {
+            let range = page * WIDTH..(page + 1) * WIDTH;
+            let (start, end) = if self.stale {
+                (0, WIDTH)
+            } else {
+                diff_columns(&frame.buffer[range.clone()], &self.shown[range])?
+            };
+            Some(DirtySpan {
+                page: page as u8,
+                start: start as u8,
+                end: end as u8,
+            })
+        })
+    }
+
+    /// Records `frame` as what the panel shows, call once every span was sent
+    pub fn mark_shown(&mut self, frame: &FrameBuffer) {
+        self.shown = frame.buffer;
+        self.stale = false;
+    }
+}
+
+/// First and one past the last column where two pages differ, None if they are identical
+fn diff_columns(current: &[u8], shown: &[u8]) -> Option<(usize, usize)> {
+    let start = current.iter().zip(shown).position(|(a, b)| a != b)?;
+    let end = current.len()
+        - current
+            .iter()
+            .rev()
+            .zip(shown.iter().rev())
+            .position(|(a, b)| a != b)?;
+    Some((start, end))
+}
+
+impl OriginDimensions for FrameBuffer {
+    fn size(&self) -> Size {
+        if self.rotation.is_portrait() {
+            Size::new(HEIGHT as u32, WIDTH as u32)
+        } else {
+            Size::new(WIDTH as u32, HEIGHT as u32)
+        }
+    }
+}
+
+impl DrawTarget for FrameBuffer {
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, color) in pixels {
+            self.put(point.x, point.y, color.is_on());
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.buffer.fill(if color.is_on() { 0xFF } else { 0x00 });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mirror that already shows `frame`
+    fn showing(frame: &FrameBuffer) -> PanelMirror {
+        let mut mirror = PanelMirror::new();
+        mirror.mark_shown(frame);
+        mirror
+    }
+
+    fn spans(mirror: &PanelMirror, frame: &FrameBuffer) -> heapless::Vec<DirtySpan, PAGES> {
+        mirror.dirty_spans(frame).collect()
+    }
+
+    #[test]
+    fn stale_mirror_sends_whole_pages() {
+        let frame = FrameBuffer::new();
+        let spans = spans(&PanelMirror::new(), &frame);
+        assert_eq!(spans.len(), PAGES);
+        for (page, span) in spans.iter().enumerate() {
+            assert_eq!(span.page as usize, page);
+            assert_eq!(span.len(), WIDTH);
+        }
+    }
+
+    #[test]
+    fn unchanged_frame_has_no_spans() {
+        let mut frame = FrameBuffer::new();
+        frame.set_pixel(5, 5, true);
+        let mirror = showing(&frame);
+        assert!(spans(&mirror, &frame).is_empty());
+    }
+
+    #[test]
+    fn single_pixel_gives_one_column() {
+        let mut frame = FrameBuffer::new();
+        let mirror = showing(&frame);
+        frame.set_pixel(37, 20, true);
+        assert_eq!(
+            spans(&mirror, &frame),
+            [DirtySpan {
+                page: 2,
+                start: 37,
+                end: 38,
+            }]
+        );
+    }
+
+    #[test]
+    fn changes_in_one_page_merge() {
+        let mut frame = FrameBuffer::new();
+        let mirror = showing(&frame);
+        frame.set_pixel(90, 8, true);
+        frame.set_pixel(12, 15, true);
+        frame.set_pixel(40, 11, true);
+        assert_eq!(
+            spans(&mirror, &frame),
+            [DirtySpan {
+                page: 1,
+                start: 12,
+                end: 91,
+            }]
+        );
+    }
+
+    #[test]
+    fn changes_in_two_pages_give_two_spans() {
+        let mut frame = FrameBuffer::new();
+        let mirror = showing(&frame);
+        frame.set_pixel(0, 0, true);
+        frame.set_pixel(WIDTH as u32 - 1, 31, true);
+        assert_eq!(
+            spans(&mirror, &frame),
+            [
+                DirtySpan {
+                    page: 0,
+                    start: 0,
+                    end: 1,
+                },
+                DirtySpan {
+                    page: 3,
+                    start: WIDTH as u8 - 1,
+                    end: WIDTH as u8,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn cleared_pixel_counts_as_change() {
+        let mut frame = FrameBuffer::new();
+        frame.set_pixel(64, 3, true);
+        let mirror = showing(&frame);
+        frame.set_pixel(64, 3, false);
+        assert_eq!(spans(&mirror, &frame).len(), 1);
+    }
+
+    #[test]
+    fn lit_pixels_read_back_in_every_rotation() {
+        for rotation in [
+            Rotation::Deg0,
+            Rotation::Deg90,
+            Rotation::Deg180,
+            Rotation::Deg270,
+        ] {
+            let mut frame = FrameBuffer::new();
+            frame.set_rotation(rotation);
+            frame.set_offset(1, 2);
+            frame.set_pixel(3, 4, true);
+            assert!(frame.is_lit(3, 4), "{rotation:?}");
+            assert!(!frame.is_lit(4, 3), "{rotation:?}");
+        }
+    }
+}
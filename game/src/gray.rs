@@ -0,0 +1,203 @@
+//! Fake grayscale on the on/off panel by temporal dithering.
+//!
+//! A gray pixel is lit on some frames and dark on others, following a 2x2 ordered pattern
+//! that shifts every frame. Each pixel is lit on `n` of every 4 frames and each frame
+//! lights `n` of every 4 neighbouring pixels, so the flicker blends into a gray at a high
+//! enough frame rate. Below [`MIN_TEMPORAL_FPS`] the pattern stands still and gray turns
+//! into a steady stipple, which never flickers. A stipple needs neighbours to look gray, so
+//! isolated pixels like stars are then drawn lit instead of vanishing, see
+//! [`Dither::is_point_lit`].
+//!
+//! This module has no hardware dependencies so it can be exercised on the host.
+
+use embedded_graphics::{pixelcolor::BinaryColor, prelude::*, primitives::Rectangle};
+
+/// Frame rate below which the pattern stops moving instead of flickering visibly
+pub const MIN_TEMPORAL_FPS: u32 = 20;
+
+/// Threshold of each pixel in a 2x2 block, row by row
+const BAYER: [u8; 4] = [0, 2, 3, 1];
+
+/// Brightness of a dithered pixel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gray {
+    /// Lit a quarter of the time
+    Dark,
+    /// Lit half of the time
+    Mid,
+    /// Lit three quarters of the time
+    Light,
+    /// Always lit
+    White,
+}
+
+impl Gray {
+    /// Frames out of every 4 the pixel is lit
+    fn quarters(self) -> u8 {
+        match self {
+            Gray::Dark => 1,
+            Gray::Mid => 2,
+            Gray::Light => 3,
+            Gray::White => 4,
+        }
+    }
+}
+
+/// The dither pattern of the frame being drawn
+#[derive(Debug, Clone, Copy)]
+pub struct Dither {
+    enabled: bool,
+    phase: u8,
+    /// Whether the pattern moved on the last frame, i.e. the frame rate is high enough
+    moving: bool,
+}
+
+impl Dither {
+    /// With dithering disabled every gray is drawn fully lit, like before
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            phase: 0,
+            moving: false,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Moves to the next frame's pattern, call once per drawn frame with the frame rate
+    /// actually reached. The pattern only moves at [`MIN_TEMPORAL_FPS`] and above.
+    pub fn advance(&mut self, fps: u32) {
+        self.moving = self.enabled && fps >= MIN_TEMPORAL_FPS;
+        if self.moving {
+            self.phase = (self.phase + 1) % 4;
+        }
+    }
+
+    /// Whether a pixel of `level` at `x`, `y` is lit in this frame
+    pub fn is_lit(&self, level: Gray, x: i32, y: i32) -> bool {
+        if !self.enabled {
+            return true;
+        }
+        let threshold = BAYER[((y & 1) * 2 + (x & 1)) as usize];
+        (threshold + self.phase) % 4 < level.quarters()
+    }
+
+    /// Like [`Self::is_lit`] for a pixel with no lit neighbours. While the pattern stands
+    /// still it's always lit, a stipple of one pixel would light only a quarter of them.
+    pub fn is_point_lit(&self, level: Gray, x: i32, y: i32) -> bool {
+        !self.moving || self.is_lit(level, x, y)
+    }
+
+    /// Wraps a draw target so that lit pixels drawn through it come out in `level`
+    pub fn draw_gray<'a, D>(&self, level: Gray, target: &'a mut D) -> GrayTarget<'a, D>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        GrayTarget {
+            target,
+            dither: *self,
+            level,
+        }
+    }
+}
+
+/// Draw target returned by [`Dither::draw_gray`]
+pub struct GrayTarget<'a, D> {
+    target: &'a mut D,
+    dither: Dither,
+    level: Gray,
+}
+
+impl<D> Dimensions for GrayTarget<'_, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.target.bounding_box()
+    }
+}
+
+impl<D> DrawTarget for GrayTarget<'_, D>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    type Color = BinaryColor;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (dither, level) = (self.dither, self.level);
+        self.target.draw_iter(
+            pixels.into_iter().filter(|Pixel(point, color)| {
+                color.is_off() || dither.is_lit(level, point.x, point.y)
+            }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pixels of a 2x2 block lit in the current frame
+    fn lit_in_block(dither: &Dither, level: Gray, point: bool) -> usize {
+        (0..4)
+            .filter(|i| {
+                let (x, y) = (i % 2, i / 2);
+                if point {
+                    dither.is_point_lit(level, x, y)
+                } else {
+                    dither.is_lit(level, x, y)
+                }
+            })
+            .count()
+    }
+
+    #[test]
+    fn each_level_lights_its_share_of_a_block() {
+        let mut dither = Dither::new(true);
+        for _ in 0..4 {
+            dither.advance(MIN_TEMPORAL_FPS);
+            for (level, lit) in [
+                (Gray::Dark, 1),
+                (Gray::Mid, 2),
+                (Gray::Light, 3),
+                (Gray::White, 4),
+            ] {
+                assert_eq!(lit_in_block(&dither, level, false), lit);
+            }
+        }
+    }
+
+    #[test]
+    fn moving_pattern_lights_a_point_on_its_share_of_frames() {
+        let mut dither = Dither::new(true);
+        let lit = (0..4)
+            .filter(|_| {
+                dither.advance(MIN_TEMPORAL_FPS);
+                dither.is_point_lit(Gray::Dark, 5, 3)
+            })
+            .count();
+        assert_eq!(lit, 1);
+    }
+
+    #[test]
+    fn still_pattern_keeps_points_lit() {
+        let mut dither = Dither::new(true);
+        dither.advance(MIN_TEMPORAL_FPS - 1);
+        assert_eq!(lit_in_block(&dither, Gray::Dark, false), 1);
+        assert_eq!(lit_in_block(&dither, Gray::Dark, true), 4);
+    }
+
+    #[test]
+    fn disabled_dither_lights_everything() {
+        let mut dither = Dither::new(false);
+        dither.advance(60);
+        assert_eq!(lit_in_block(&dither, Gray::Dark, false), 4);
+        assert_eq!(lit_in_block(&dither, Gray::Dark, true), 4);
+    }
+}
@@ -0,0 +1,63 @@
+/// Number of scores kept
+pub const ENTRIES: usize = 5;
+
+/// Best scores so far, highest first. Empty places are 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Leaderboard {
+    scores: [u32; ENTRIES],
+}
+
+impl Leaderboard {
+    /// Bytes used by [`Self::to_bytes`]
+    pub const SIZE: usize = ENTRIES * 4;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scores from best to worst, including empty places
+    pub fn scores(&self) -> &[u32; ENTRIES] {
+        &self.scores
+    }
+
+    /// The high score, 0 if nothing has been scored yet
+    pub fn best(&self) -> u32 {
+        self.scores[0]
+    }
+
+    /// Adds a finished game's score, returns its place (0 = best) if it made the board
+    pub fn insert(&mut self, score: u32) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+        // Ties go below the existing score, the earlier game keeps its place
+        let place = self.scores.iter().position(|&s| score > s)?;
+        self.scores.copy_within(place..ENTRIES - 1, place + 1);
+        self.scores[place] = score;
+        Some(place)
+    }
+
+    pub fn clear(&mut self) {
+        self.scores = [0; ENTRIES];
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        for (chunk, score) in bytes.chunks_exact_mut(4).zip(self.scores) {
+            chunk.copy_from_slice(&score.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes stored scores, returns None if they aren't in order
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Option<Self> {
+        let mut scores = [0; ENTRIES];
+        for (score, chunk) in scores.iter_mut().zip(bytes.chunks_exact(4)) {
+            *score = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        scores
+            .windows(2)
+            .all(|pair| pair[0] >= pair[1])
+            .then_some(Self { scores })
+    }
+}
@@ -0,0 +1,26 @@
+//! Everything of the game that doesn't touch the hardware: the simulation, drawing into
+//! the frame buffer, menus, text and the console command parser. The firmware crate puts
+//! it on the board, while the tests here run on the host with
+//! `cargo +stable test -p asteroids-game`.
+
+#![no_std]
+
+pub mod asteroid;
+pub mod attract;
+pub mod collision;
+pub mod command;
+pub mod effects;
+pub mod fixed;
+pub mod fonts;
+pub mod framebuffer;
+pub mod gray;
+pub mod leaderboard;
+pub mod movement;
+pub mod particles;
+pub mod rng;
+pub mod settings;
+pub mod sprite;
+pub mod starfield;
+pub mod state;
+pub mod strings;
+pub mod ui;
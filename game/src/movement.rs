@@ -0,0 +1,62 @@
+use crate::fixed::Fixed;
+use crate::state::AXIS_MAX;
+
+/// Tunable constants for inertia-based ship movement, all in pixels per frame
+#[derive(Debug, Clone, Copy)]
+pub struct InertiaTuning {
+    /// Velocity gained per frame at full axis deflection
+    pub acceleration: Fixed,
+    /// Top speed in either direction
+    pub max_speed: Fixed,
+    /// Velocity lost per frame while no thrust is applied
+    pub friction: Fixed,
+}
+
+impl Default for InertiaTuning {
+    fn default() -> Self {
+        // Reaches top speed in 8 frames and coasts to a stop in 12 at 30 fps
+        Self {
+            acceleration: Fixed::from_ratio(3, 8),
+            max_speed: Fixed::from_int(3),
+            friction: Fixed::from_ratio(1, 4),
+        }
+    }
+}
+
+/// How the axis input moves the ship
+#[derive(Debug, Clone, Copy)]
+pub enum Movement {
+    /// Speed is proportional to the axis and stops instantly
+    Direct { speed: Fixed },
+    /// The axis accelerates the ship, which coasts and slows down under friction
+    Inertia(InertiaTuning),
+}
+
+impl Default for Movement {
+    /// The classic fixed 3 px per frame
+    fn default() -> Self {
+        Movement::Direct {
+            speed: Fixed::from_int(3),
+        }
+    }
+}
+
+impl Movement {
+    /// Advances one frame, updating the position and velocity from the axis input
+    pub fn step(&self, position: &mut Fixed, velocity: &mut Fixed, axis: i8) {
+        match self {
+            Movement::Direct { speed } => {
+                *velocity = speed.scale(axis as i32, AXIS_MAX as i32);
+            }
+            Movement::Inertia(tuning) => {
+                if axis == 0 {
+                    *velocity = velocity.approach_zero(tuning.friction);
+                } else {
+                    *velocity += tuning.acceleration.scale(axis as i32, AXIS_MAX as i32);
+                    *velocity = (*velocity).clamp(-tuning.max_speed, tuning.max_speed);
+                }
+            }
+        }
+        *position += *velocity;
+    }
+}
@@ -0,0 +1,148 @@
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{Line, PrimitiveStyle},
+};
+
+use crate::fixed::Fixed;
+use crate::rng::Rng;
+
+/// How a particle is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleStyle {
+    /// A single pixel
+    Pixel,
+    /// A short streak trailing behind the particle along its velocity
+    Line,
+}
+
+/// Shape of a burst of debris
+#[derive(Debug, Clone, Copy)]
+pub struct Burst {
+    pub count: usize,
+    /// Largest velocity on each axis in pixels per frame
+    pub speed: Fixed,
+    /// Longest lifetime in frames, individual particles live between half and all of it
+    pub life: u8,
+    pub style: ParticleStyle,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    x: Fixed,
+    y: Fixed,
+    vx: Fixed,
+    vy: Fixed,
+    /// Frames left before the particle disappears
+    life: u8,
+    style: ParticleStyle,
+}
+
+/// Fixed-capacity pool of short-lived particles for debris and engine effects.
+///
+/// All randomness comes from the caller's [`Rng`], so effects replay identically for a seed.
+pub struct Particles<const N: usize> {
+    particles: heapless::Vec<Particle, N>,
+}
+
+impl<const N: usize> Default for Particles<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Particles<N> {
+    pub fn new() -> Self {
+        Self {
+            particles: heapless::Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    /// Spawns particles flying outwards from a point in random directions.
+    /// Particles that don't fit in the pool are dropped.
+    pub fn burst(&mut self, rng: &mut Rng, x: i32, y: i32, burst: &Burst) {
+        let max = burst.speed.raw();
+        let life = burst.life;
+        for _ in 0..burst.count {
+            let particle = Particle {
+                x: Fixed::from_int(x),
+                y: Fixed::from_int(y),
+                vx: Fixed::from_raw(rng.range(-max, max + 1)),
+                vy: Fixed::from_raw(rng.range(-max, max + 1)),
+                // Stagger lifetimes so the debris fades out instead of vanishing at once
+                life: life - rng.range(0, life as i32 / 2 + 1) as u8,
+                style: burst.style,
+            };
+            if self.particles.push(particle).is_err() {
+                break;
+            }
+        }
+    }
+
+    /// Emits a single exhaust particle drifting down from a point
+    pub fn exhaust(&mut self, rng: &mut Rng, x: i32, y: i32) {
+        let _ = self.particles.push(Particle {
+            x: Fixed::from_int(x + rng.range(-1, 2)),
+            y: Fixed::from_int(y),
+            vx: Fixed::from_raw(rng.range(-32, 33)),
+            vy: Fixed::from_raw(rng.range(256, 448)),
+            life: rng.range(4, 8) as u8,
+            style: ParticleStyle::Pixel,
+        });
+    }
+
+    /// Moves every particle one frame and removes the expired ones
+    pub fn update(&mut self) {
+        let mut i = 0;
+        while i < self.particles.len() {
+            let particle = &mut self.particles[i];
+            particle.x += particle.vx;
+            particle.y += particle.vy;
+            particle.life = particle.life.saturating_sub(1);
+
+            if particle.life == 0 {
+                self.particles.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    pub fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        for particle in &self.particles {
+            let head = Point::new(particle.x.round(), particle.y.round());
+            match particle.style {
+                ParticleStyle::Pixel => Pixel(head, BinaryColor::On).draw(target)?,
+                ParticleStyle::Line => {
+                    let tail = Point::new(
+                        (particle.x - particle.vx).round(),
+                        (particle.y - particle.vy).round(),
+                    );
+                    Line::new(tail, head)
+                        .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                        .draw(target)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
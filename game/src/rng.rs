@@ -0,0 +1,32 @@
+/// Small deterministic pseudo-random generator (xorshift32) used for all gameplay randomness,
+/// so a game can be replayed exactly from its seed
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u32,
+}
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        // xorshift gets stuck on zero, so remap it to an arbitrary non-zero state
+        Self {
+            state: if seed == 0 { 0x9E3779B9 } else { seed },
+        }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Returns a value in `min..max`, or `min` if the range is empty
+    pub fn range(&mut self, min: i32, max: i32) -> i32 {
+        if max <= min {
+            return min;
+        }
+        min + (self.next_u32() % (max - min) as u32) as i32
+    }
+}
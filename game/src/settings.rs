@@ -0,0 +1,377 @@
+/// How bullets are fired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FireMode {
+    /// A bullet spawns automatically every few frames
+    Auto,
+    /// Bullets spawn only while the fire button is pressed
+    Manual,
+}
+
+impl FireMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FireMode::Auto => "Auto",
+            FireMode::Manual => "Manual",
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(FireMode::Auto),
+            1 => Some(FireMode::Manual),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            FireMode::Auto => 0,
+            FireMode::Manual => 1,
+        }
+    }
+}
+
+/// Number of background stars, or none at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StarDensity {
+    Off,
+    Low,
+    Medium,
+    High,
+}
+
+impl StarDensity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StarDensity::Off => "Off",
+            StarDensity::Low => "Low",
+            StarDensity::Medium => "Medium",
+            StarDensity::High => "High",
+        }
+    }
+
+    /// Number of stars drawn at this density
+    pub fn star_count(&self) -> usize {
+        match self {
+            StarDensity::Off => 0,
+            StarDensity::Low => 12,
+            StarDensity::Medium => 24,
+            StarDensity::High => 40,
+        }
+    }
+
+    /// The next density, wrapping around, for cycling through in the settings
+    pub fn next(self) -> Self {
+        match self {
+            StarDensity::Off => StarDensity::Low,
+            StarDensity::Low => StarDensity::Medium,
+            StarDensity::Medium => StarDensity::High,
+            StarDensity::High => StarDensity::Off,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(StarDensity::Off),
+            1 => Some(StarDensity::Low),
+            2 => Some(StarDensity::Medium),
+            3 => Some(StarDensity::High),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            StarDensity::Off => 0,
+            StarDensity::Low => 1,
+            StarDensity::Medium => 2,
+            StarDensity::High => 3,
+        }
+    }
+}
+
+/// Clockwise rotation of the playfield on the panel, 90 and 270 give a portrait playfield
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Deg0,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl Rotation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rotation::Deg0 => "0",
+            Rotation::Deg90 => "90",
+            Rotation::Deg180 => "180",
+            Rotation::Deg270 => "270",
+        }
+    }
+
+    /// Whether the playfield is taller than wide
+    pub fn is_portrait(&self) -> bool {
+        matches!(self, Rotation::Deg90 | Rotation::Deg270)
+    }
+
+    /// The next rotation, wrapping around, for cycling through in the settings
+    pub fn next(self) -> Self {
+        match self {
+            Rotation::Deg0 => Rotation::Deg90,
+            Rotation::Deg90 => Rotation::Deg180,
+            Rotation::Deg180 => Rotation::Deg270,
+            Rotation::Deg270 => Rotation::Deg0,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Rotation::Deg0),
+            1 => Some(Rotation::Deg90),
+            2 => Some(Rotation::Deg180),
+            3 => Some(Rotation::Deg270),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Rotation::Deg0 => 0,
+            Rotation::Deg90 => 1,
+            Rotation::Deg180 => 2,
+            Rotation::Deg270 => 3,
+        }
+    }
+}
+
+/// How hits between bullets, asteroids and the ship are detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hitbox {
+    /// Distance between centers, forgiving but ignores the actual shapes
+    Circle,
+    /// Overlap of the drawn pixels
+    Pixel,
+}
+
+impl Hitbox {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Hitbox::Circle => "Circle",
+            Hitbox::Pixel => "Pixel",
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Hitbox::Circle),
+            1 => Some(Hitbox::Pixel),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Hitbox::Circle => 0,
+            Hitbox::Pixel => 1,
+        }
+    }
+}
+
+/// Panel brightness
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Contrast {
+    Low,
+    Medium,
+    High,
+    Max,
+}
+
+impl Contrast {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Contrast::Low => "Low",
+            Contrast::Medium => "Medium",
+            Contrast::High => "High",
+            Contrast::Max => "Max",
+        }
+    }
+
+    /// Contrast register value sent to the panel, medium is the driver default
+    pub fn value(&self) -> u8 {
+        match self {
+            Contrast::Low => 0x10,
+            Contrast::Medium => 0x5F,
+            Contrast::High => 0x9F,
+            Contrast::Max => 0xFF,
+        }
+    }
+
+    /// The next level, wrapping around, for cycling through in the settings
+    pub fn next(self) -> Self {
+        match self {
+            Contrast::Low => Contrast::Medium,
+            Contrast::Medium => Contrast::High,
+            Contrast::High => Contrast::Max,
+            Contrast::Max => Contrast::Low,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Contrast::Low),
+            1 => Some(Contrast::Medium),
+            2 => Some(Contrast::High),
+            3 => Some(Contrast::Max),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Contrast::Low => 0,
+            Contrast::Medium => 1,
+            Contrast::High => 2,
+            Contrast::Max => 3,
+        }
+    }
+}
+
+/// Whether to guard the OLED against burn-in by shifting the frame and showing a
+/// screensaver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BurnInGuard {
+    Off,
+    On,
+}
+
+impl BurnInGuard {
+    pub fn label(&self) -> &'static str {
+        match self {
+            BurnInGuard::Off => "Off",
+            BurnInGuard::On => "On",
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(BurnInGuard::Off),
+            1 => Some(BurnInGuard::On),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            BurnInGuard::Off => 0,
+            BurnInGuard::On => 1,
+        }
+    }
+}
+
+/// Language of the text on screen, the serial console always speaks English
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    /// Name of the language in itself, so it can be found without reading the current one
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// Short name shown in the settings
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "EN",
+            Language::German => "DE",
+        }
+    }
+
+    /// The next language, wrapping around, for cycling through in the settings
+    pub fn next(self) -> Self {
+        match self {
+            Language::English => Language::German,
+            Language::German => Language::English,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Language::English),
+            1 => Some(Language::German),
+            _ => None,
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Language::English => 0,
+            Language::German => 1,
+        }
+    }
+}
+
+/// User preferences persisted in flash
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub fire_mode: FireMode,
+    pub star_density: StarDensity,
+    pub rotation: Rotation,
+    pub hitbox: Hitbox,
+    pub burn_in: BurnInGuard,
+    pub contrast: Contrast,
+    pub language: Language,
+}
+
+impl Settings {
+    /// Size of the serialized settings in bytes
+    pub const SIZE: usize = 7;
+
+    /// Defaults for a board, manual fire is only preferred when there is something to fire with
+    pub fn defaults(has_fire_button: bool, rotation: Rotation) -> Self {
+        Self {
+            fire_mode: if has_fire_button {
+                FireMode::Manual
+            } else {
+                FireMode::Auto
+            },
+            star_density: StarDensity::Medium,
+            rotation,
+            hitbox: Hitbox::Circle,
+            burn_in: BurnInGuard::On,
+            contrast: Contrast::Medium,
+            language: Language::English,
+        }
+    }
+
+    /// Deserialize settings, returns None if the bytes don't hold valid settings
+    pub fn from_bytes(bytes: &[u8; Self::SIZE]) -> Option<Self> {
+        Some(Self {
+            fire_mode: FireMode::from_byte(bytes[0])?,
+            star_density: StarDensity::from_byte(bytes[1])?,
+            rotation: Rotation::from_byte(bytes[2])?,
+            hitbox: Hitbox::from_byte(bytes[3])?,
+            burn_in: BurnInGuard::from_byte(bytes[4])?,
+            contrast: Contrast::from_byte(bytes[5])?,
+            language: Language::from_byte(bytes[6])?,
+        })
+    }
+
+    /// Serialize settings, unused bytes are reserved for future settings
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0] = self.fire_mode.to_byte();
+        bytes[1] = self.star_density.to_byte();
+        bytes[2] = self.rotation.to_byte();
+        bytes[3] = self.hitbox.to_byte();
+        bytes[4] = self.burn_in.to_byte();
+        bytes[5] = self.contrast.to_byte();
+        bytes[6] = self.language.to_byte();
+        bytes
+    }
+}
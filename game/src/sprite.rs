@@ -0,0 +1,80 @@
+use embedded_graphics::{
+    image::{GetPixel, ImageRaw},
+    pixelcolor::BinaryColor,
+    prelude::*,
+};
+
+/// A 1bpp bitmap with one or more animation frames.
+///
+/// The constants at the bottom of this module are generated by `build.rs` from the
+/// PBM/PNG files in `assets/sprites`, so artwork can change without touching this code.
+pub struct Sprite {
+    frames: &'static [ImageRaw<'static, BinaryColor>],
+}
+
+impl Sprite {
+    pub const fn new(frames: &'static [ImageRaw<'static, BinaryColor>]) -> Self {
+        Self { frames }
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Size of every frame in pixels
+    pub fn size(&self) -> Size {
+        self.frames[0].size()
+    }
+
+    /// Frame bitmap, indices past the end hold the last frame
+    pub fn frame(&self, index: usize) -> &ImageRaw<'static, BinaryColor> {
+        &self.frames[index.min(self.frames.len() - 1)]
+    }
+
+    /// Draws a frame centered on `center`. Unlit pixels are transparent, so sprites can
+    /// overlap whatever is already drawn.
+    pub fn draw<D>(&self, index: usize, center: Point, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let frame = self.frame(index);
+        let size = frame.size();
+        let top_left = center - Point::new(size.width as i32 / 2, size.height as i32 / 2);
+
+        let lit = frame
+            .bounding_box()
+            .points()
+            .filter(|&point| frame.pixel(point) == Some(BinaryColor::On))
+            .map(|point| Pixel(top_left + point, BinaryColor::On));
+        target.draw_iter(lit)
+    }
+}
+
+/// Playback position of a sprite animation that runs once
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    tick: u16,
+    ticks_per_frame: u16,
+}
+
+impl Animation {
+    pub fn new(ticks_per_frame: u16) -> Self {
+        Self {
+            tick: 0,
+            ticks_per_frame: ticks_per_frame.max(1),
+        }
+    }
+
+    /// Advances one tick, returns false once every frame of `sprite` has been shown
+    pub fn advance(&mut self, sprite: &Sprite) -> bool {
+        self.tick = self.tick.saturating_add(1);
+        self.frame() < sprite.frame_count()
+    }
+
+    /// Index of the frame to draw
+    pub fn frame(&self) -> usize {
+        (self.tick / self.ticks_per_frame) as usize
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/sprites.rs"));
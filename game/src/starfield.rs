@@ -0,0 +1,85 @@
+use crate::fixed::Fixed;
+use crate::rng::Rng;
+
+/// Most stars the field can hold
+pub const MAX_STARS: usize = 40;
+
+/// Number of parallax layers
+pub const LAYERS: usize = 3;
+
+/// Downward speed of each parallax layer in pixels per frame, farthest first
+const LAYER_SPEEDS: [Fixed; LAYERS] = [
+    Fixed::from_ratio(1, 4),
+    Fixed::from_ratio(1, 2),
+    Fixed::from_int(1),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Star {
+    x: u8,
+    y: Fixed,
+    layer: u8,
+}
+
+/// Multi-layer starfield scrolling down behind the playfield.
+///
+/// Stars are plain pixels handed straight to the frame buffer, so drawing a full field
+/// costs a few microseconds.
+pub struct Starfield {
+    stars: heapless::Vec<Star, MAX_STARS>,
+    width: u32,
+    height: u32,
+}
+
+impl Starfield {
+    pub fn new(rng: &mut Rng, count: usize, width: u32, height: u32) -> Self {
+        let mut starfield = Self {
+            stars: heapless::Vec::new(),
+            width,
+            height,
+        };
+        starfield.set_count(rng, count);
+        starfield
+    }
+
+    /// Rebuilds the field with a new number of stars, capped at [`MAX_STARS`]
+    pub fn set_count(&mut self, rng: &mut Rng, count: usize) {
+        self.stars.clear();
+        for i in 0..count.min(MAX_STARS) {
+            let star = Star {
+                x: rng.range(0, self.width as i32) as u8,
+                y: Fixed::from_int(rng.range(0, self.height as i32)),
+                // Far layers get the most stars, like real depth
+                layer: match i % 6 {
+                    0..=2 => 0,
+                    3 | 4 => 1,
+                    _ => 2,
+                },
+            };
+            let _ = self.stars.push(star);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stars.is_empty()
+    }
+
+    /// Scrolls every layer by its speed, stars leaving the bottom reappear at a random column
+    pub fn update(&mut self, rng: &mut Rng) {
+        let height = Fixed::from_int(self.height as i32);
+        for star in &mut self.stars {
+            star.y += LAYER_SPEEDS[star.layer as usize];
+            if star.y >= height {
+                star.y -= height;
+                star.x = rng.range(0, self.width as i32) as u8;
+            }
+        }
+    }
+
+    /// Calls `set_pixel` with the position and layer of every star, layer 0 is the farthest
+    pub fn draw(&self, mut set_pixel: impl FnMut(u32, u32, usize)) {
+        for star in &self.stars {
+            set_pixel(star.x as u32, star.y.floor() as u32, star.layer as usize);
+        }
+    }
+}
@@ -0,0 +1,49 @@
+/// Full deflection of the horizontal axis in either direction
+pub const AXIS_MAX: i8 = 127;
+
+/// Holds the current state of inputs for each frame
+#[derive(Default, Debug, Clone, Copy)]
+pub struct State {
+    pub button_left: bool,
+    pub button_right: bool,
+    /// Fire/select, e.g. the push switch of a rotary encoder
+    pub button_fire: bool,
+    /// Horizontal movement from -127 (full left) to 127 (full right).
+    /// Digital buttons report full deflection, analog sources report proportionally.
+    pub axis: i8,
+    /// Relative paddle-style movement in encoder detents since the last frame,
+    /// positive = clockwise (right)
+    pub detents: i8,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self {
+            button_left: false,
+            button_right: false,
+            button_fire: false,
+            axis: 0,
+            detents: 0,
+        }
+    }
+
+    /// Adds a movement contribution to the axis, clamping to the valid range
+    pub fn push_axis(&mut self, amount: i8) {
+        self.axis =
+            (self.axis as i16 + amount as i16).clamp(-(AXIS_MAX as i16), AXIS_MAX as i16) as i8;
+    }
+
+    /// Adds encoder detents, clamping to the valid range
+    pub fn push_detents(&mut self, detents: i16) {
+        self.detents = (self.detents as i16 + detents).clamp(i8::MIN as i16, i8::MAX as i16) as i8;
+    }
+
+    /// Returns true if any input is active this frame
+    pub fn has_input(&self) -> bool {
+        self.button_left
+            || self.button_right
+            || self.button_fire
+            || self.axis != 0
+            || self.detents != 0
+    }
+}
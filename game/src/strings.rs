@@ -0,0 +1,299 @@
+//! Text shown on screen, in every language the settings offer.
+//!
+//! Each language maps every [`Message`] id to its text with an exhaustive match, so one
+//! left out fails to compile. A compile time check also makes sure every text has the
+//! right number of `{}` placeholders. This module has no hardware dependencies so it can
+//! be exercised on the host.
+
+use core::fmt::{Display, Write};
+
+use crate::settings::Language;
+
+/// Id of a piece of on-screen text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// Title of the settings screen
+    Settings,
+    ReleaseForSettings,
+    /// Seconds left until the scores are reset
+    ScoreResetIn,
+    /// The high score, in the HUD font
+    HighScore,
+    Demo,
+    GameOver,
+    PressAnyButton,
+    HighScores,
+    Credits1,
+    Credits2,
+    Credits3,
+    Fire,
+    Auto,
+    Manual,
+    Stars,
+    Rotation,
+    Hitbox,
+    Circle,
+    Pixel,
+    BurnIn,
+    Contrast,
+    Language,
+    Diagnostics,
+    ResetScores,
+    Back,
+    ResetScoresQuestion,
+    ClearLogQuestion,
+    Yes,
+    No,
+    LastCrash,
+    NoCrash,
+}
+
+impl Message {
+    /// Every message in declaration order, for the checks below
+    const ALL: &[Message] = &[
+        Message::Settings,
+        Message::ReleaseForSettings,
+        Message::ScoreResetIn,
+        Message::HighScore,
+        Message::Demo,
+        Message::GameOver,
+        Message::PressAnyButton,
+        Message::HighScores,
+        Message::Credits1,
+        Message::Credits2,
+        Message::Credits3,
+        Message::Fire,
+        Message::Auto,
+        Message::Manual,
+        Message::Stars,
+        Message::Rotation,
+        Message::Hitbox,
+        Message::Circle,
+        Message::Pixel,
+        Message::BurnIn,
+        Message::Contrast,
+        Message::Language,
+        Message::Diagnostics,
+        Message::ResetScores,
+        Message::Back,
+        Message::ResetScoresQuestion,
+        Message::ClearLogQuestion,
+        Message::Yes,
+        Message::No,
+        Message::LastCrash,
+        Message::NoCrash,
+    ];
+
+    /// Number of `{}` placeholders the message has in every language
+    const fn placeholders(self) -> usize {
+        match self {
+            Message::ScoreResetIn | Message::HighScore => 1,
+            _ => 0,
+        }
+    }
+}
+
+const fn english(id: Message) -> &'static str {
+    match id {
+        Message::Settings => "Settings",
+        Message::ReleaseForSettings => "Release: Settings",
+        Message::ScoreResetIn => "Score Reset in {}",
+        Message::HighScore => "HS:{}",
+        Message::Demo => "DEMO",
+        Message::GameOver => "GAME OVER",
+        Message::PressAnyButton => "Press any button",
+        Message::HighScores => "High scores",
+        Message::Credits1 => "Written in Rust",
+        Message::Credits2 => "with esp-hal and",
+        Message::Credits3 => "embedded-graphics",
+        Message::Fire => "Fire",
+        Message::Auto => "Auto",
+        Message::Manual => "Manual",
+        Message::Stars => "Stars",
+        Message::Rotation => "Rotation",
+        Message::Hitbox => "Hitbox",
+        Message::Circle => "Circle",
+        Message::Pixel => "Pixel",
+        Message::BurnIn => "Burn-in",
+        Message::Contrast => "Contrast",
+        Message::Language => "Language",
+        Message::Diagnostics => "Diagnostics",
+        Message::ResetScores => "Reset scores",
+        Message::Back => "Back",
+        Message::ResetScoresQuestion => "Reset scores?",
+        Message::ClearLogQuestion => "Clear log?",
+        Message::Yes => "Yes",
+        Message::No => "No",
+        Message::LastCrash => "Last crash:",
+        Message::NoCrash => "No crash recorded",
+    }
+}
+
+// Kept short enough for the 64 pixel wide portrait playfield, like the English text
+const fn german(id: Message) -> &'static str {
+    match id {
+        Message::Settings => "Optionen",
+        Message::ReleaseForSettings => "Loslassen: Optionen",
+        Message::ScoreResetIn => "Löschen in {}",
+        Message::HighScore => "BEST:{}",
+        Message::Demo => "DEMO",
+        Message::GameOver => "SPIELENDE",
+        Message::PressAnyButton => "Taste drücken",
+        Message::HighScores => "Bestenliste",
+        Message::Credits1 => "In Rust mit",
+        Message::Credits2 => "esp-hal und",
+        Message::Credits3 => "embedded-graphics",
+        Message::Fire => "Feuer",
+        Message::Auto => "Auto",
+        Message::Manual => "Manuell",
+        Message::Stars => "Sterne",
+        Message::Rotation => "Drehung",
+        Message::Hitbox => "Treffer",
+        Message::Circle => "Kreis",
+        Message::Pixel => "Pixel",
+        Message::BurnIn => "Bildschutz",
+        Message::Contrast => "Kontrast",
+        Message::Language => "Sprache",
+        Message::Diagnostics => "Diagnose",
+        Message::ResetScores => "Liste löschen",
+        Message::Back => "Zurück",
+        Message::ResetScoresQuestion => "Liste löschen?",
+        Message::ClearLogQuestion => "Log löschen?",
+        Message::Yes => "Ja",
+        Message::No => "Nein",
+        Message::LastCrash => "Absturz:",
+        Message::NoCrash => "Kein Absturz",
+    }
+}
+
+// Fails to compile unless every text has the placeholders its message needs
+const _: () = {
+    let mut i = 0;
+    while i < Message::ALL.len() {
+        let id = Message::ALL[i];
+        assert!(id as usize == i, "Message::ALL is missing a message");
+        let texts = [english(id), german(id)];
+        let mut j = 0;
+        while j < texts.len() {
+            assert!(!texts[j].is_empty(), "message left empty");
+            assert!(
+                placeholders(texts[j]) == id.placeholders(),
+                "wrong number of placeholders"
+            );
+            j += 1;
+        }
+        i += 1;
+    }
+};
+
+/// Number of `{}` in `text`
+const fn placeholders(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let (mut count, mut i) = (0, 0);
+    while i + 1 < bytes.len() {
+        if bytes[i] == b'{' && bytes[i + 1] == b'}' {
+            count += 1;
+            i += 2;
+        } else {
+            i += 1;
+        }
+    }
+    count
+}
+
+/// Text of a message in a language
+pub fn text(language: Language, id: Message) -> &'static str {
+    match language {
+        Language::English => english(id),
+        Language::German => german(id),
+    }
+}
+
+/// Text of a message with its `{}` placeholders filled in order. Whatever doesn't fit
+/// into `N` bytes is cut off at a character boundary instead of failing.
+pub fn format<const N: usize>(
+    language: Language,
+    id: Message,
+    args: &[&dyn Display],
+) -> heapless::String<N> {
+    let mut out = heapless::String::new();
+    let mut writer = Truncating::new(&mut out);
+    for (i, piece) in text(language, id).split("{}").enumerate() {
+        if let Some(arg) = i.checked_sub(1).and_then(|i| args.get(i)) {
+            let _ = write!(writer, "{}", arg);
+        }
+        let _ = writer.write_str(piece);
+    }
+    out
+}
+
+/// Writes as much as fits into a string, dropping the rest instead of failing. Once
+/// something was dropped later writes are too, so a short piece can't follow a cut one.
+pub struct Truncating<'a, const N: usize> {
+    out: &'a mut heapless::String<N>,
+    full: bool,
+}
+
+impl<'a, const N: usize> Truncating<'a, N> {
+    pub fn new(out: &'a mut heapless::String<N>) -> Self {
+        Self { out, full: false }
+    }
+}
+
+impl<const N: usize> Write for Truncating<'_, N> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            if self.full || self.out.push(c).is_err() {
+                self.full = true;
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_fills_placeholders() {
+        let text: heapless::String<16> = format(Language::English, Message::HighScore, &[&1234u32]);
+        assert_eq!(text, "HS:1234");
+        let text: heapless::String<32> = format(Language::German, Message::ScoreResetIn, &[&3u8]);
+        assert_eq!(text, "Löschen in 3");
+    }
+
+    #[test]
+    fn format_without_placeholders_copies_the_text() {
+        let text: heapless::String<32> = format(Language::German, Message::Back, &[]);
+        assert_eq!(text, "Zurück");
+    }
+
+    #[test]
+    fn format_leaves_missing_arguments_empty() {
+        let text: heapless::String<16> = format(Language::English, Message::HighScore, &[]);
+        assert_eq!(text, "HS:");
+    }
+
+    #[test]
+    fn format_truncates_at_a_character_boundary() {
+        // "ö" takes two bytes, so it doesn't fit into the third
+        let text: heapless::String<2> = format(Language::German, Message::ScoreResetIn, &[&3]);
+        assert_eq!(text, "L");
+        let text: heapless::String<3> = format(Language::German, Message::ScoreResetIn, &[&3]);
+        assert_eq!(text, "Lö");
+        let text: heapless::String<5> = format(Language::English, Message::HighScore, &[&98765]);
+        assert_eq!(text, "HS:98");
+    }
+
+    #[test]
+    fn every_language_has_every_message() {
+        assert_eq!(Message::ALL.last(), Some(&Message::NoCrash));
+        for language in [Language::English, Language::German] {
+            for &id in Message::ALL {
+                assert!(!text(language, id).is_empty(), "{language:?} {id:?}");
+            }
+        }
+    }
+}
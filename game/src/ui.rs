@@ -0,0 +1,454 @@
+//! Widgets for the menu screens: aligned text, a scrolling menu with toggles and sliders,
+//! and yes/no dialogs, all driven by two buttons.
+//!
+//! Left moves to the next entry, a press of right selects it and holding right goes back.
+//! This module has no hardware dependencies so it can be exercised on the host.
+
+use embedded_graphics::{
+    mono_font::{MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, PrimitiveStyleBuilder, Rectangle},
+    text::Text,
+};
+
+/// How long right must be held to go back instead of selecting
+pub const HOLD_MS: u64 = 800;
+
+/// Space kept free at the left and right edge of the screen
+const MARGIN: i32 = 2;
+
+/// Cells of a slider bar are this wide, plus a pixel of space
+const SLIDER_CELL: i32 = 3;
+
+/// Horizontal placement of a line of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// Width of `text` in pixels
+pub fn text_width(text: &str, font: &MonoFont) -> i32 {
+    let advance = font.character_size.width + font.character_spacing;
+    text.chars().count() as i32 * advance as i32
+}
+
+/// Left edge of `text` aligned within the columns `left..left + width`
+pub fn aligned_x(text: &str, font: &MonoFont, align: Align, left: i32, width: i32) -> i32 {
+    match align {
+        Align::Left => left,
+        Align::Center => left + (width - text_width(text, font)) / 2,
+        Align::Right => left + width - text_width(text, font),
+    }
+}
+
+/// Draws a line of text with its baseline at `y`, aligned within the screen margins
+pub fn draw_text<D>(
+    target: &mut D,
+    text: &str,
+    font: &MonoFont,
+    align: Align,
+    y: i32,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let width = target.bounding_box().size.width as i32 - 2 * MARGIN;
+    draw_text_in(target, text, font, align, MARGIN, width, y)
+}
+
+/// Draws a line of text with its baseline at `y`, aligned within `left..left + width`
+pub fn draw_text_in<D>(
+    target: &mut D,
+    text: &str,
+    font: &MonoFont,
+    align: Align,
+    left: i32,
+    width: i32,
+    y: i32,
+) -> Result<(), D::Error>
+where
+    D: DrawTarget<Color = BinaryColor>,
+{
+    let x = aligned_x(text, font, align, left, width);
+    let style = MonoTextStyle::new(font, BinaryColor::On);
+    Text::new(text, Point::new(x, y), style).draw(target)?;
+    Ok(())
+}
+
+/// Distance between the baselines of two lines of text
+pub fn line_height(font: &MonoFont) -> i32 {
+    font.character_size.height as i32 + 2
+}
+
+/// What the two buttons did, see [`Nav::update`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavEvent {
+    Next,
+    Select,
+    Back,
+}
+
+/// Turns the two buttons into menu navigation
+#[derive(Debug, Clone, Copy)]
+pub struct Nav {
+    left_held: bool,
+    right_held: bool,
+    /// When right went down, until it's released or held long enough to go back
+    right_since: Option<u64>,
+}
+
+impl Default for Nav {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Nav {
+    /// Buttons already held when a screen opens are ignored until they're released, so
+    /// the press that opened it doesn't act on it too
+    pub fn new() -> Self {
+        Self {
+            left_held: true,
+            right_held: true,
+            right_since: None,
+        }
+    }
+
+    /// Call every frame with the button levels and the time in milliseconds.
+    /// Left acts when pressed, right when released or once held for [`HOLD_MS`].
+    pub fn update(&mut self, left: bool, right: bool, now_ms: u64) -> Option<NavEvent> {
+        let mut event = None;
+        if left && !self.left_held {
+            event = Some(NavEvent::Next);
+        }
+        if right && !self.right_held {
+            self.right_since = Some(now_ms);
+        }
+        if let Some(since) = self.right_since {
+            if !right {
+                self.right_since = None;
+                event = Some(NavEvent::Select);
+            } else if now_ms.saturating_sub(since) >= HOLD_MS {
+                self.right_since = None;
+                event = Some(NavEvent::Back);
+            }
+        }
+        self.left_held = left;
+        self.right_held = right;
+        event
+    }
+}
+
+/// An entry of a [`Menu`]
+#[derive(Debug, Clone, Copy)]
+pub enum Item<'a> {
+    /// Runs an action or opens another screen
+    Action(&'a str),
+    /// A setting shown by name
+    Choice { label: &'a str, value: &'a str },
+    /// An on/off setting shown as a check box
+    Toggle { label: &'a str, on: bool },
+    /// A setting from 0 to `max` shown as a bar
+    Slider { label: &'a str, value: u8, max: u8 },
+}
+
+/// Vertical list of entries with a cursor, scrolling when they don't all fit
+#[derive(Debug, Clone, Copy)]
+pub struct Menu {
+    cursor: usize,
+    len: usize,
+}
+
+impl Menu {
+    pub fn new(len: usize) -> Self {
+        Self { cursor: 0, len }
+    }
+
+    /// Index of the selected entry
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Moves the cursor down, wrapping around to the first entry
+    pub fn next(&mut self) {
+        self.cursor = (self.cursor + 1) % self.len;
+    }
+
+    /// Entries that fit below the title on a screen `height` pixels tall
+    pub fn visible_rows(height: i32, font: &MonoFont) -> usize {
+        // The title's baseline is a pixel below the glyph tops, see `draw`
+        ((height - 1 - font.character_size.height as i32) / line_height(font)).max(1) as usize
+    }
+
+    /// Draws the title and the entries around the cursor
+    pub fn draw<D>(
+        &self,
+        target: &mut D,
+        font: &MonoFont,
+        title: &str,
+        items: &[Item],
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let size = target.bounding_box().size;
+        let (width, line) = (size.width as i32, line_height(font));
+        let title_y = font.baseline as i32 + 1;
+        draw_text(target, title, font, Align::Left, title_y)?;
+
+        let visible = Self::visible_rows(size.height as i32, font);
+        let first = (self.cursor + 1).saturating_sub(visible);
+        let label_x = MARGIN + 2 * font.character_size.width as i32;
+        let value_right = width - MARGIN;
+        let style = MonoTextStyle::new(font, BinaryColor::On);
+
+        for (row, (i, item)) in items
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(visible)
+            .enumerate()
+        {
+            let y = title_y + (row as i32 + 1) * line;
+            if i == self.cursor {
+                Text::new(">", Point::new(MARGIN, y), style).draw(target)?;
+            }
+            let label = match *item {
+                Item::Action(label)
+                | Item::Choice { label, .. }
+                | Item::Toggle { label, .. }
+                | Item::Slider { label, .. } => label,
+            };
+            Text::new(label, Point::new(label_x, y), style).draw(target)?;
+
+            // Values hang from the right edge, boxes sit on the baseline like the text
+            let box_size = font.baseline;
+            let top = y - box_size as i32 + 1;
+            match *item {
+                Item::Action(_) => {}
+                Item::Choice { value, .. } => {
+                    let x = value_right - text_width(value, font);
+                    Text::new(value, Point::new(x, y), style).draw(target)?;
+                }
+                Item::Toggle { on, .. } => {
+                    // Filled when on, the small font leaves no room for a mark inside
+                    let style = if on {
+                        PrimitiveStyle::with_fill(BinaryColor::On)
+                    } else {
+                        PrimitiveStyle::with_stroke(BinaryColor::On, 1)
+                    };
+                    Rectangle::new(
+                        Point::new(value_right - box_size as i32, top),
+                        Size::new(box_size, box_size),
+                    )
+                    .into_styled(style)
+                    .draw(target)?;
+                }
+                Item::Slider { value, max, .. } => {
+                    let bar_width = max as i32 * (SLIDER_CELL + 1) + 3;
+                    let left = value_right - bar_width;
+                    Rectangle::new(Point::new(left, top), Size::new(bar_width as u32, box_size))
+                        .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+                        .draw(target)?;
+                    for cell in 0..value.min(max) as i32 {
+                        Rectangle::new(
+                            Point::new(left + 2 + cell * (SLIDER_CELL + 1), top + 2),
+                            Size::new(SLIDER_CELL as u32, box_size.saturating_sub(4).max(1)),
+                        )
+                        .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+                        .draw(target)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A yes/no question drawn over the screen it came from
+#[derive(Debug, Clone, Copy)]
+pub struct Dialog {
+    question: &'static str,
+    /// Texts of the two answers
+    answers: (&'static str, &'static str),
+    yes: bool,
+}
+
+impl Dialog {
+    /// Starts on `no`, so a stray press never confirms
+    pub fn new(question: &'static str, yes: &'static str, no: &'static str) -> Self {
+        Self {
+            question,
+            answers: (yes, no),
+            yes: false,
+        }
+    }
+
+    /// Next switches the answer, select gives it and back answers no.
+    /// Returns the answer once one is given.
+    pub fn update(&mut self, event: NavEvent) -> Option<bool> {
+        match event {
+            NavEvent::Next => {
+                self.yes = !self.yes;
+                None
+            }
+            NavEvent::Select => Some(self.yes),
+            NavEvent::Back => Some(false),
+        }
+    }
+
+    /// Draws a framed box with the question above the two answers, the chosen one
+    /// highlighted
+    pub fn draw<D>(&self, target: &mut D, font: &MonoFont) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let size = target.bounding_box().size;
+        let line = line_height(font);
+        let frame = Rectangle::new(
+            Point::new(MARGIN, (size.height as i32 - 2 * line - 6) / 2),
+            Size::new(size.width - 2 * MARGIN as u32, 2 * line as u32 + 6),
+        );
+        frame
+            .into_styled(
+                PrimitiveStyleBuilder::new()
+                    .fill_color(BinaryColor::Off)
+                    .stroke_color(BinaryColor::On)
+                    .stroke_width(1)
+                    .build(),
+            )
+            .draw(target)?;
+
+        let question_y = frame.top_left.y + 3 + font.baseline as i32;
+        draw_text(target, self.question, font, Align::Center, question_y)?;
+
+        // Each answer takes half of the box, centered in it
+        let half = frame.size.width as i32 / 2;
+        let answer_y = question_y + line;
+        for (i, (answer, chosen)) in [(self.answers.0, self.yes), (self.answers.1, !self.yes)]
+            .into_iter()
+            .enumerate()
+        {
+            let x = aligned_x(
+                answer,
+                font,
+                Align::Center,
+                frame.top_left.x + i as i32 * half,
+                half,
+            );
+            let (text, background) = if chosen {
+                (BinaryColor::Off, BinaryColor::On)
+            } else {
+                (BinaryColor::On, BinaryColor::Off)
+            };
+            let style = MonoTextStyleBuilder::new()
+                .font(font)
+                .text_color(text)
+                .background_color(background)
+                .build();
+            Text::new(answer, Point::new(x, answer_y), style).draw(target)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::mono_font::ascii::{FONT_4X6, FONT_6X10};
+
+    /// A Nav with both buttons released, so the next press counts
+    fn released() -> Nav {
+        let mut nav = Nav::new();
+        assert_eq!(nav.update(false, false, 0), None);
+        nav
+    }
+
+    #[test]
+    fn buttons_held_when_opened_are_ignored() {
+        let mut nav = Nav::new();
+        assert_eq!(nav.update(true, true, 0), None);
+        assert_eq!(nav.update(true, true, 2 * HOLD_MS), None);
+        assert_eq!(nav.update(false, false, 2 * HOLD_MS), None);
+        assert_eq!(nav.update(true, false, 2 * HOLD_MS), Some(NavEvent::Next));
+    }
+
+    #[test]
+    fn left_press_moves_once() {
+        let mut nav = released();
+        assert_eq!(nav.update(true, false, 10), Some(NavEvent::Next));
+        assert_eq!(nav.update(true, false, 20), None);
+        assert_eq!(nav.update(false, false, 30), None);
+        assert_eq!(nav.update(true, false, 40), Some(NavEvent::Next));
+    }
+
+    #[test]
+    fn right_tap_selects_on_release() {
+        let mut nav = released();
+        assert_eq!(nav.update(false, true, 100), None);
+        assert_eq!(nav.update(false, true, 100 + HOLD_MS - 1), None);
+        assert_eq!(
+            nav.update(false, false, 100 + HOLD_MS - 1),
+            Some(NavEvent::Select)
+        );
+    }
+
+    #[test]
+    fn right_hold_goes_back_once() {
+        let mut nav = released();
+        assert_eq!(nav.update(false, true, 100), None);
+        assert_eq!(nav.update(false, true, 100 + HOLD_MS), Some(NavEvent::Back));
+        assert_eq!(nav.update(false, true, 100 + 3 * HOLD_MS), None);
+        assert_eq!(nav.update(false, false, 100 + 3 * HOLD_MS), None);
+    }
+
+    #[test]
+    fn dialog_starts_on_no() {
+        let mut dialog = Dialog::new("Sure?", "Yes", "No");
+        assert_eq!(dialog.update(NavEvent::Select), Some(false));
+    }
+
+    #[test]
+    fn dialog_next_switches_the_answer() {
+        let mut dialog = Dialog::new("Sure?", "Yes", "No");
+        assert_eq!(dialog.update(NavEvent::Next), None);
+        assert_eq!(dialog.update(NavEvent::Select), Some(true));
+        assert_eq!(dialog.update(NavEvent::Next), None);
+        assert_eq!(dialog.update(NavEvent::Select), Some(false));
+    }
+
+    #[test]
+    fn dialog_back_answers_no() {
+        let mut dialog = Dialog::new("Sure?", "Yes", "No");
+        dialog.update(NavEvent::Next);
+        assert_eq!(dialog.update(NavEvent::Back), Some(false));
+    }
+
+    #[test]
+    fn menu_cursor_wraps() {
+        let mut menu = Menu::new(3);
+        menu.next();
+        menu.next();
+        assert_eq!(menu.cursor(), 2);
+        menu.next();
+        assert_eq!(menu.cursor(), 0);
+    }
+
+    #[test]
+    fn visible_rows_fill_the_screen_below_the_title() {
+        assert_eq!(Menu::visible_rows(64, &FONT_6X10), 4);
+        assert_eq!(Menu::visible_rows(128, &FONT_4X6), 15);
+        assert_eq!(Menu::visible_rows(8, &FONT_6X10), 1);
+    }
+
+    #[test]
+    fn alignment_within_columns() {
+        assert_eq!(text_width("abc", &FONT_6X10), 18);
+        assert_eq!(aligned_x("abc", &FONT_6X10, Align::Left, 10, 40), 10);
+        assert_eq!(aligned_x("abc", &FONT_6X10, Align::Center, 10, 40), 21);
+        assert_eq!(aligned_x("abc", &FONT_6X10, Align::Right, 10, 40), 32);
+    }
+}
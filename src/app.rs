@@ -1,61 +1,317 @@
 use embedded_graphics::{
-    mono_font::{MonoTextStyle, ascii::FONT_6X10},
+    mono_font::{
+        MonoFont, MonoTextStyle,
+        iso_8859_1::{FONT_4X6, FONT_6X10},
+    },
     pixelcolor::BinaryColor,
     prelude::*,
-    primitives::{Line, PrimitiveStyle, Triangle},
-    text::Text,
+    primitives::{Line, PrimitiveStyle, Rectangle},
+    text::{Baseline, Text},
 };
 use esp_hal::clock::CpuClock;
-use esp_hal::gpio::{Input, InputConfig, Pull};
-use esp_hal::time::Rate;
+use esp_hal::gpio::{AnyPin, Input, InputConfig, Pull};
+use esp_hal::rtc_cntl::Rtc;
+use esp_hal::rtc_cntl::sleep::{GpioWakeupSource, TimerWakeupSource};
 use esp_hal::time::{Duration, Instant};
-use esp_hal::{i2c::master::Config as I2cConfig, i2c::master::I2c};
+use esp_hal::uart::{Config as UartConfig, UartRx};
 use esp_println::println;
 use esp_storage::FlashStorage;
-use ssd1306::{Ssd1306, prelude::*};
 
+use crate::asteroid::{self, Asteroid, AsteroidShape, MAX_ASTEROIDS};
+use crate::attract::{Page, Pilot, Threat};
+use crate::collision::{self, MAX_MASK_HEIGHT};
+use crate::command::{self, Command};
+use crate::console::Console;
+use crate::crash::{self, CrashLog};
+use crate::display;
+use crate::effects::{Effects, Transition};
+use crate::encoder::{Encoder, EncoderPins};
+use crate::fixed::Fixed;
+use crate::fonts;
+use crate::framebuffer::FrameBuffer;
+use crate::gray::{Dither, Gray};
+use crate::input::{self, Buttons, InputSource};
+use crate::joystick::Joystick;
+use crate::leaderboard::Leaderboard;
+use crate::movement::Movement;
+#[cfg(feature = "debug-overlay")]
+use crate::overlay::{DebugOverlay, EntityCounts, FrameTimes, Usage};
+use crate::particles::{Burst, ParticleStyle, Particles};
+use crate::presenter::Presenter;
+use crate::rng::Rng;
+use crate::settings::{BurnInGuard, Contrast, FireMode, Hitbox, Rotation, Settings, StarDensity};
+use crate::sprite::{self, Animation};
+use crate::starfield::{self, Starfield};
 use crate::state::State;
 use crate::storage;
+use crate::strings::{self, Message};
+use crate::ui::{self, Align, Dialog, Item, Menu, Nav, NavEvent};
 
-type Display = Ssd1306<
-    I2CInterface<I2c<'static, esp_hal::Blocking>>,
-    DisplaySize128x64,
-    ssd1306::mode::BufferedGraphicsMode<DisplaySize128x64>,
->;
-
-struct Asteroid {
-    x: i32,
-    y: i32,
-    radius: u32,
-    seed: u32,
+/// GPIOs the display is wired to
+#[cfg(not(feature = "spi-display"))]
+const DISPLAY_PINS: [u8; 2] = [21, 22];
+#[cfg(feature = "spi-display")]
+const DISPLAY_PINS: [u8; 5] = [14, 13, 15, 4, 16];
+
+/// Closest the ship gets to the left and right edge, keeping the wings on screen
+const SHIP_EDGE_MARGIN: i32 = 8;
+
+/// Ship movement in pixels per rotary encoder detent
+const DETENT_DISTANCE: i32 = 4;
+
+/// Frames each explosion sprite frame stays on screen
+const EXPLOSION_FRAME_TICKS: u16 = 3;
+
+/// Gray of each starfield layer with dithering on, farthest first
+const STAR_GRAYS: [Gray; starfield::LAYERS] = [Gray::Dark, Gray::Mid, Gray::White];
+
+/// Rows at the top of the playfield where incoming asteroids fade in with dithering on
+const ASTEROID_FADE_ROWS: i32 = 16;
+
+/// Debris thrown off by a destroyed asteroid
+const ASTEROID_DEBRIS: Burst = Burst {
+    count: 6,
+    speed: Fixed::from_ratio(3, 2),
+    life: 10,
+    style: ParticleStyle::Pixel,
+};
+
+/// Debris thrown off when an asteroid hits the ship
+const SHIP_DEBRIS: Burst = Burst {
+    count: 12,
+    speed: Fixed::from_int(2),
+    life: 16,
+    style: ParticleStyle::Line,
+};
+
+/// Frames the screen shakes and how far, when an asteroid hits the ship
+const HIT_SHAKE_FRAMES: u8 = 10;
+const HIT_SHAKE_STRENGTH: u8 = 2;
+
+/// Frames the panel flashes inverted when an asteroid hits the ship
+const HIT_FLASH_FRAMES: u8 = 3;
+
+/// Frames the game runs in slow motion after the ship is hit, and how much slower
+const HIT_SLOW_MOTION_FRAMES: u16 = 30;
+const HIT_SLOW_MOTION_FACTOR: u8 = 3;
+
+/// Font of the score line, small enough to leave the playfield almost the whole panel
+const HUD_FONT: &MonoFont = &fonts::FONT_3X5;
+
+/// Baseline of the score line
+const HUD_BASELINE: i32 = 5;
+
+/// How long the final score stays up after the ship is hit
+const GAME_OVER_BANNER: Duration = Duration::from_secs(2);
+
+/// Frames between bullets in autofire mode
+const AUTO_FIRE_COOLDOWN: u32 = 10;
+
+/// Frames between bullets while the fire button is held in manual mode
+const MANUAL_FIRE_COOLDOWN: u32 = 5;
+
+/// How long both buttons must be held before releasing them opens the settings
+const SETTINGS_HOLD: Duration = Duration::from_secs(2);
+
+/// Longest press of both buttons that counts as a tap, two taps toggle the debug overlay
+const DOUBLE_TAP_PRESS: Duration = Duration::from_millis(400);
+
+/// Most time between the two taps of a double tap
+#[cfg(feature = "debug-overlay")]
+const DOUBLE_TAP_GAP: Duration = Duration::from_secs(1);
+
+/// Whole-frame offsets the burn-in guard cycles through, so no pixel stays lit for good
+const SHIFT_PATTERN: [(i32, i32); 4] = [(0, 0), (1, 0), (1, 1), (0, 1)];
+
+/// How long the frame stays at each offset of [`SHIFT_PATTERN`]
+const SHIFT_PERIOD: Duration = Duration::from_secs(60);
+
+/// Panel contrast while dimmed, or the contrast setting if that is lower
+const DIM_CONTRAST: u8 = 0x08;
+
+/// Longest light sleep before checking the inputs that can't wake the chip, like the
+/// serial console
+const LIGHT_SLEEP_CHECK: core::time::Duration = core::time::Duration::from_secs(1);
+
+/// How long the screensaver runs before sleep, at most half the sleep timeout
+const SCREENSAVER_TIME: Duration = Duration::from_secs(30);
+
+/// Text bouncing around the screensaver
+const SCREENSAVER_TEXT: &str = "ESP Asteroids";
+
+/// Which screen is currently active
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scene {
+    /// Title screen, cycling through the attract mode pages while idle
+    Title,
+    Playing,
+    Settings,
+    /// Crash log of the last panic, opened from the settings
+    Diagnostics,
+}
+
+/// What a yes/no dialog asks to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Confirm {
+    ResetScores,
+    ClearCrashLog,
+}
+
+/// An entry of the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsEntry {
+    Fire,
+    Stars,
+    Rotation,
+    Hitbox,
+    BurnIn,
+    Contrast,
+    Language,
+    Diagnostics,
+    ResetScores,
+    /// Saves the settings and goes back to the game
+    Back,
+}
+
+impl SettingsEntry {
+    /// Every entry in display order, the menu cursor indexes into this
+    const ALL: [SettingsEntry; 10] = [
+        SettingsEntry::Fire,
+        SettingsEntry::Stars,
+        SettingsEntry::Rotation,
+        SettingsEntry::Hitbox,
+        SettingsEntry::BurnIn,
+        SettingsEntry::Contrast,
+        SettingsEntry::Language,
+        SettingsEntry::Diagnostics,
+        SettingsEntry::ResetScores,
+        SettingsEntry::Back,
+    ];
 }
 
 pub struct AppConfig {
     pub target_fps: u32,
+    /// Seconds of inactivity before dimming the display (0 = never)
+    pub dim_timeout_secs: u32,
     /// Seconds of inactivity before entering sleep mode (display off + 4 fps, 0 = disabled)
     pub sleep_timeout_secs: u32,
+    /// Seconds of inactivity before sleep mode goes deeper, light sleeping the chip between
+    /// input checks. A button press still wakes it at once. (0 = disabled)
+    pub deep_sleep_timeout_secs: u32,
+    /// Seconds of inactivity in a game before returning to the title screen and its
+    /// attract mode (0 = disabled)
+    pub attract_timeout_secs: u32,
+    /// Read an analog thumbstick on GPIO34 (ADC1) in addition to the buttons
+    pub joystick: bool,
+    /// Pins of a rotary encoder with push switch, if one is wired
+    pub encoder: Option<EncoderPins>,
+    /// GPIO of a dedicated fire button (active low), if one is wired
+    pub fire_button: Option<u8>,
+    /// Accept key controls and `:` commands on the UART0 receive line (GPIO3)
+    pub serial_console: bool,
+    /// How the ship responds to left/right input
+    pub movement: Movement,
+    /// Dither the starfield, incoming asteroids and fading explosions into shades of gray
+    pub grayscale: bool,
+    /// Playfield rotation until one is picked in the settings, 90/270 give a portrait playfield
+    pub rotation: Rotation,
 }
 
 pub struct App {
-    display: Display,
-    triangle_x: i32,
+    /// Sends finished frames to the display from the other core
+    presenter: Presenter,
+    /// Frame being drawn, handed to the presenter once complete
+    frame: FrameBuffer,
+    /// Playfield size in the current rotation
+    width: i32,
+    height: i32,
+    triangle_x: Fixed,
+    /// Horizontal ship velocity in pixels per frame
+    triangle_vx: Fixed,
     triangle_y: i32,
+    /// Collision mask rows of the ship sprite
+    ship_rows: heapless::Vec<u32, MAX_MASK_HEIGHT>,
+    movement: Movement,
+    target_fps: u32,
     frame_duration: Duration,
     sleep_frame_duration: Duration,
+    /// When the frame in progress started
+    last_frame_start: Instant,
+    /// Running average of the time between frame starts, in microseconds
+    frame_period_avg: u64,
+    dim_timeout: Duration,
     sleep_timeout: Duration,
+    deep_sleep_timeout: Duration,
+    attract_timeout: Duration,
     last_input_time: Instant,
     is_sleeping: bool,
+    /// Light sleeping between input checks, the last idle stage
+    is_deep_sleeping: bool,
+    rtc: Rtc<'static>,
     bullets: heapless::Vec<(i32, i32), 16>,
     bullet_cooldown: u32,
-    asteroids: heapless::Vec<Asteroid, 8>,
+    asteroids: heapless::Vec<Asteroid, MAX_ASTEROIDS>,
     asteroid_cooldown: u32,
+    /// Fragments of split asteroids left out for lack of a free slot, since boot
+    dropped_fragments: u32,
+    /// Position and playback of each running explosion animation
+    explosions: heapless::Vec<(i32, i32, Animation), 8>,
+    particles: Particles<32>,
+    starfield: Starfield,
+    /// Gray pattern of the frame being drawn
+    dither: Dither,
+    /// Shake, flash, slow motion and scene transitions in progress
+    effects: Effects,
     frame_count: u32,
     score: u32,
-    high_score: u32,
+    /// Score of the game that just ended and when, while its banner is up
+    game_over: Option<(u32, Instant)>,
+    leaderboard: Leaderboard,
     both_buttons_held_start: Option<Instant>,
+    /// When both buttons were last tapped together, for the double tap gesture
+    #[cfg(feature = "debug-overlay")]
+    last_both_tap: Option<Instant>,
     flash: FlashStorage<'static>,
-    button_left: Input<'static>,
-    button_right: Input<'static>,
+    buttons: Buttons,
+    joystick: Option<Joystick>,
+    encoder: Option<Encoder>,
+    console: Option<Console>,
+    rng: Rng,
+    /// True if the board has a fire button or encoder switch, required for manual fire
+    has_fire_input: bool,
+    settings: Settings,
+    scene: Scene,
+    /// Button navigation of the settings and diagnostics screens
+    nav: Nav,
+    settings_menu: Menu,
+    /// Question shown over the settings or diagnostics screen, and what it confirms
+    dialog: Option<(Confirm, Dialog)>,
+    /// First crash log line shown on the diagnostics screen
+    log_scroll: usize,
+    /// Description of the last panic, until it's cleared on the diagnostics screen
+    crash_log: Option<CrashLog>,
+    attract_page: Page,
+    attract_page_start: Instant,
+    /// Plays the attract mode demo
+    pilot: Pilot,
+    /// Position in [`SHIFT_PATTERN`] and when it was reached
+    shift_index: usize,
+    shift_start: Instant,
+    dimmed: bool,
+    screensaver: bool,
+    /// Top left corner and direction of the screensaver text
+    saver_position: Point,
+    saver_direction: Point,
+    prev_state: State,
+    /// Frame timing totals since the last `stats` report, in microseconds
+    frame_time_total: u64,
+    starfield_time_total: u64,
+    timed_frames: u32,
+    #[cfg(feature = "debug-overlay")]
+    overlay: DebugOverlay,
+    /// Timings of the frame in progress, for the overlay
+    #[cfg(feature = "debug-overlay")]
+    frame_times: FrameTimes,
 }
 
 impl App {
@@ -75,56 +331,224 @@ impl App {
         );
 
         println!("Buttons configured on GPIO18 (left) and GPIO19 (right)");
-        // Configure I2C - SDA on GPIO21, SCL on GPIO22
-        let i2c = I2c::new(
-            peripherals.I2C0,
-            I2cConfig::default().with_frequency(Rate::from_hz(400_000)),
-        )
-        .unwrap()
-        .with_sda(peripherals.GPIO21)
-        .with_scl(peripherals.GPIO22);
-        println!("Initializing display...");
 
-        // Create the display interface
-        let interface = I2CInterface::new(i2c, 0x3C, 0x40);
+        // Pins the firmware drives itself, inputs configured by number must stay off them.
+        // GPIO1 and GPIO3 are UART0, which carries the log output and the console.
+        let mut pins_in_use: heapless::Vec<u8, 16> = heapless::Vec::new();
+        pins_in_use.extend_from_slice(&[1, 3, 18, 19]).unwrap();
+        pins_in_use.extend_from_slice(&DISPLAY_PINS).unwrap();
+
+        let button_fire = config
+            .fire_button
+            .filter(|&pin| claim_pins(&mut pins_in_use, &[pin], "Fire button"))
+            .map(|pin| {
+                println!("Fire button configured on GPIO{}", pin);
+                // Safety: `claim_pins` checked the pin isn't used by anything else
+                Input::new(
+                    unsafe { AnyPin::steal(pin) },
+                    InputConfig::default().with_pull(Pull::Up),
+                )
+            });
 
-        // Create the display driver
-        let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
-            .into_buffered_graphics_mode();
+        // Both buttons held during boot requests a fresh joystick calibration
+        let recalibrate = button_left.is_low() && button_right.is_low();
+        let mut buttons = Buttons::new(button_left, button_right, button_fire);
+        buttons.enable_wakeup();
+        let rtc = Rtc::new(peripherals.LPWR);
 
-        display.init().unwrap();
-        display.clear(BinaryColor::Off).unwrap();
+        println!("Initializing display...");
+
+        // Frames are drawn into our own buffer and sent by the presenter
+        #[cfg(not(feature = "spi-display"))]
+        let display =
+            display::init(peripherals.I2C0, peripherals.GPIO21, peripherals.GPIO22).unwrap();
+        #[cfg(feature = "spi-display")]
+        let display = display::init(
+            peripherals.SPI2,
+            display::SpiPins {
+                sck: peripherals.GPIO14,
+                mosi: peripherals.GPIO13,
+                cs: peripherals.GPIO15,
+                dc: peripherals.GPIO4,
+                rst: peripherals.GPIO16,
+            },
+        )
+        .unwrap();
 
         let frame_duration = Duration::from_millis((1000 / config.target_fps) as u64);
         // Sleep frame rate is 4 fps (250ms per frame) to save power while still checking for input
         let sleep_frame_duration = Duration::from_millis(250);
 
+        let dim_timeout = Duration::from_secs(config.dim_timeout_secs as u64);
         let sleep_timeout = Duration::from_secs(config.sleep_timeout_secs as u64);
+        let deep_sleep_timeout = Duration::from_secs(config.deep_sleep_timeout_secs as u64);
+        let attract_timeout = Duration::from_secs(config.attract_timeout_secs as u64);
+
+        // The display task on the APP core is parked while flash is written
+        let mut flash = FlashStorage::new(peripherals.FLASH).multicore_auto_park();
+        let leaderboard = storage::load_leaderboard(&mut flash);
+        println!("Loaded high score from flash: {}", leaderboard.best());
+        let crash_log = storage::load_crash_log(&mut flash);
+        if let Some(log) = &crash_log {
+            println!("Restarted after a crash: {}", log);
+        }
+
+        let joystick = if config.joystick {
+            let stored = storage::load_calibration(&mut flash).filter(|_| !recalibrate);
+            let mut joystick = Joystick::new(
+                peripherals.ADC1,
+                peripherals.GPIO34,
+                stored.unwrap_or_default(),
+            );
+            if stored.is_none() {
+                // Assume the stick is resting at boot and remember that as its center
+                let calibration = joystick.calibrate();
+                if let Err(e) = storage::save_calibration(&calibration, &mut flash) {
+                    println!("Failed to save joystick calibration: {:?}", e);
+                } else {
+                    println!(
+                        "Joystick calibrated: center {}, deadzone {}",
+                        calibration.center, calibration.deadzone
+                    );
+                }
+            }
+            println!("Joystick configured on GPIO34");
+            Some(joystick)
+        } else {
+            None
+        };
+
+        let encoder = config
+            .encoder
+            .filter(|pins| {
+                claim_pins(
+                    &mut pins_in_use,
+                    &[pins.a, pins.b, pins.switch],
+                    "Rotary encoder",
+                )
+            })
+            .map(|pins| {
+                println!(
+                    "Rotary encoder configured on GPIO{}/GPIO{} (switch GPIO{})",
+                    pins.a, pins.b, pins.switch
+                );
+                // Safety: `claim_pins` checked the pins against each other and all in use
+                unsafe { Encoder::new(peripherals.PCNT, pins) }
+            });
+
+        let console = if config.serial_console {
+            let rx = UartRx::new(peripherals.UART0, UartConfig::default())
+                .unwrap()
+                .with_rx(peripherals.GPIO3);
+            println!(
+                "Serial console enabled: a/d or arrows to steer, space to fire, `:help` for commands"
+            );
+            Some(Console::new(rx))
+        } else {
+            None
+        };
+
+        // Only physical controls count: the console can still send fire presses, but a
+        // board played without a laptop attached must not be left unable to fire
+        let has_fire_input = buttons.has_fire() || encoder.is_some();
+        let settings = storage::load_settings(&mut flash)
+            .unwrap_or_else(|| Settings::defaults(has_fire_input, config.rotation));
+        println!("Fire mode: {}", settings.fire_mode.label());
+
+        let mut frame = FrameBuffer::new();
+        frame.set_rotation(settings.rotation);
+        let size = frame.size();
+        let (width, height) = (size.width as i32, size.height as i32);
+        println!(
+            "Playfield: {}x{} (rotation {})",
+            width,
+            height,
+            settings.rotation.label()
+        );
 
-        let mut flash = FlashStorage::new(peripherals.FLASH);
-        let saved_high_score = storage::load_high_score(&mut flash);
-        println!("Loaded high score from flash: {}", saved_high_score);
+        // Started last so setup can still write to flash with the APP core idle
+        let mut presenter = Presenter::start(peripherals.CPU_CTRL, display);
+        presenter.set_contrast(settings.contrast.value());
+
+        let mut rng = Rng::new(esp_hal::rng::Rng::new().random());
+        let starfield = Starfield::new(
+            &mut rng,
+            settings.star_density.star_count(),
+            width as u32,
+            height as u32,
+        );
 
         let mut app = Self {
             flash,
-            display,
-            triangle_x: 64,
-            triangle_y: 58, // Start near bottom of screen
+            presenter,
+            frame,
+            width,
+            height,
+            triangle_x: Fixed::from_int(width / 2),
+            triangle_vx: Fixed::ZERO,
+            triangle_y: height - 6, // Start near bottom of screen
+            ship_rows: collision::image_rows(sprite::SHIP.frame(0)),
+            movement: config.movement,
+            target_fps: config.target_fps,
             frame_duration,
             sleep_frame_duration,
+            last_frame_start: Instant::now(),
+            frame_period_avg: frame_duration.as_micros(),
+            dim_timeout,
             sleep_timeout,
+            deep_sleep_timeout,
+            attract_timeout,
             last_input_time: Instant::now(),
             is_sleeping: false,
+            is_deep_sleeping: false,
+            rtc,
             bullets: heapless::Vec::new(),
             bullet_cooldown: 0,
             asteroids: heapless::Vec::new(),
+            dropped_fragments: 0,
             asteroid_cooldown: 30, // First asteroid after 1 second
+            explosions: heapless::Vec::new(),
+            particles: Particles::new(),
+            starfield,
+            dither: Dither::new(config.grayscale),
+            effects: Effects::new(),
             frame_count: 0,
             score: 0,
-            high_score: saved_high_score,
+            game_over: None,
+            leaderboard,
             both_buttons_held_start: None,
-            button_left,
-            button_right,
+            #[cfg(feature = "debug-overlay")]
+            last_both_tap: None,
+            buttons,
+            joystick,
+            encoder,
+            console,
+            rng,
+            has_fire_input,
+            settings,
+            scene: Scene::Title,
+            nav: Nav::new(),
+            settings_menu: Menu::new(SettingsEntry::ALL.len()),
+            dialog: None,
+            log_scroll: 0,
+            crash_log,
+            attract_page: Page::Title,
+            attract_page_start: Instant::now(),
+            pilot: Pilot::new(),
+            shift_index: 0,
+            shift_start: Instant::now(),
+            dimmed: false,
+            screensaver: false,
+            saver_position: Point::zero(),
+            saver_direction: Point::new(1, 1),
+            prev_state: State::new(),
+            frame_time_total: 0,
+            starfield_time_total: 0,
+            timed_frames: 0,
+            #[cfg(feature = "debug-overlay")]
+            overlay: DebugOverlay::new(),
+            #[cfg(feature = "debug-overlay")]
+            frame_times: FrameTimes::default(),
         };
 
         app.render();
@@ -135,39 +559,44 @@ impl App {
             config.target_fps,
             1000 / config.target_fps
         );
+        if config.dim_timeout_secs > 0 {
+            println!("Dim timeout: {} seconds", config.dim_timeout_secs);
+        }
         if config.sleep_timeout_secs > 0 {
             println!(
                 "Sleep timeout: {} seconds (display off + 4 fps)",
                 config.sleep_timeout_secs
             );
+            if config.deep_sleep_timeout_secs > 0 {
+                println!(
+                    "Deep sleep timeout: {} seconds (light sleep between input checks)",
+                    config.deep_sleep_timeout_secs
+                );
+            }
         } else {
             println!("Power saving: disabled");
         }
-        println!("Use buttons to move triangle left/right. Auto-shooting bullets!");
+        println!("Press any button to start a game.");
+        println!("Use buttons to move triangle left/right. Hold both for 2s to open settings.");
 
         app
     }
 
     /// Main loop called every frame
     pub fn main_loop(&mut self, state: &State) {
-        let has_input = state.button_left || state.button_right;
+        let prev_state = core::mem::replace(&mut self.prev_state, *state);
+        let has_input = state.has_input();
         let elapsed = self.last_input_time.elapsed();
 
         // Check if we should wake up from sleep
         if self.is_sleeping && has_input {
-            println!("Waking from sleep");
-            self.is_sleeping = false;
-            self.last_input_time = Instant::now();
-            self.display.set_display_on(true).unwrap();
-            self.render();
+            self.wake();
             return;
         }
 
         // Check if we should enter sleep mode
         if !self.is_sleeping && self.sleep_timeout.as_millis() > 0 && elapsed > self.sleep_timeout {
-            println!("Entering sleep mode (display off, checking inputs at 4 fps)");
-            self.is_sleeping = true;
-            self.display.set_display_on(false).unwrap();
+            self.enter_sleep();
             return;
         }
 
@@ -176,9 +605,47 @@ impl App {
             return;
         }
 
+        if has_input {
+            self.last_input_time = Instant::now();
+        }
+
+        // First idle stage, the display stays on but dimmer
+        let dim = self.dim_timeout.as_millis() > 0 && !has_input && elapsed >= self.dim_timeout;
+        if dim != self.dimmed {
+            self.dimmed = dim;
+            self.apply_contrast();
+        }
+
+        if self.burn_in_guard(elapsed, has_input) {
+            return;
+        }
+
+        if self.scene == Scene::Settings {
+            self.settings_loop(state);
+            return;
+        }
+
+        if self.scene == Scene::Diagnostics {
+            self.diagnostics_loop(state);
+            return;
+        }
+
+        if self.scene == Scene::Title {
+            self.title_loop(state, &prev_state);
+            return;
+        }
+
+        // An abandoned game gives way to the attract mode
+        if self.attract_timeout.as_millis() > 0 && elapsed > self.attract_timeout {
+            println!("No input, back to the title screen");
+            self.show_title();
+            return;
+        }
+
         // Check if both buttons are held for high score reset
         let both_buttons = state.button_left && state.button_right;
         let mut show_reset_warning = false;
+        let mut show_settings_hint = false;
 
         if both_buttons {
             if self.both_buttons_held_start.is_none() {
@@ -187,44 +654,91 @@ impl App {
 
             let held_duration = self.both_buttons_held_start.unwrap().elapsed();
             if held_duration >= Duration::from_secs(15) {
-                self.high_score = 0;
-                if let Err(e) = storage::save_high_score(0, &mut self.flash) {
-                    println!("Failed to clear high score: {:?}", e);
-                } else {
-                    println!("High score cleared!");
-                }
+                self.reset_high_score();
                 self.both_buttons_held_start = None;
                 self.render();
                 return;
             } else if held_duration >= Duration::from_secs(10) {
                 show_reset_warning = true;
+            } else if held_duration >= SETTINGS_HOLD {
+                show_settings_hint = true;
             }
-        } else {
-            self.both_buttons_held_start = None;
+        } else if let Some(start_time) = self.both_buttons_held_start.take() {
+            // Letting go before the reset warning appears opens the settings instead
+            let held_duration = start_time.elapsed();
+            if held_duration >= SETTINGS_HOLD && held_duration < Duration::from_secs(10) {
+                println!("Opening settings");
+                self.open_settings();
+                self.render();
+                return;
+            } else if held_duration < DOUBLE_TAP_PRESS {
+                self.tap_both_buttons();
+            }
+        }
+
+        let needs_redraw = self.play_frame(state);
+
+        // The overlay's numbers change every frame, so keep it current even when idle
+        #[cfg(feature = "debug-overlay")]
+        let overlay_visible = self.overlay.is_visible();
+        #[cfg(not(feature = "debug-overlay"))]
+        let overlay_visible = false;
+        if needs_redraw
+            || show_reset_warning
+            || show_settings_hint
+            || overlay_visible
+            || self.effects.is_animating()
+        {
+            self.render();
         }
+    }
 
+    /// Advances the round by one frame with the given input, returns true if anything
+    /// on screen changed. Also plays the attract mode demo, which never touches the scores.
+    fn play_frame(&mut self, state: &State) -> bool {
+        if self.effects.skips_update() {
+            return false;
+        }
+        let demo = self.scene == Scene::Title;
         let mut needs_redraw = false;
         self.frame_count = self.frame_count.wrapping_add(1);
 
-        if state.button_left && !both_buttons {
-            self.triangle_x = self.triangle_x.saturating_sub(3).max(8);
-            needs_redraw = true;
-        }
+        // Opposing buttons cancel out on the axis, so holding both doesn't move the ship
+        let old_x = self.triangle_x.round();
+        self.movement
+            .step(&mut self.triangle_x, &mut self.triangle_vx, state.axis);
+        // Encoder detents move the ship directly, like a paddle
+        self.triangle_x += Fixed::from_int(state.detents as i32 * DETENT_DISTANCE);
 
-        if state.button_right && !both_buttons {
-            self.triangle_x = (self.triangle_x + 3).min(120);
+        let clamped_x = self.triangle_x.clamp(
+            Fixed::from_int(SHIP_EDGE_MARGIN),
+            Fixed::from_int(self.width - SHIP_EDGE_MARGIN),
+        );
+        if clamped_x != self.triangle_x {
+            // Stop dead against the screen edge instead of pushing into it
+            self.triangle_x = clamped_x;
+            self.triangle_vx = Fixed::ZERO;
+        }
+        if self.triangle_x.round() != old_x {
             needs_redraw = true;
         }
 
-        if has_input {
-            self.last_input_time = Instant::now();
-        }
+        let fire_mode = self.fire_mode();
+        let firing = match fire_mode {
+            FireMode::Auto => true,
+            FireMode::Manual => state.button_fire,
+        };
 
         if self.bullet_cooldown > 0 {
             self.bullet_cooldown -= 1;
-        } else {
-            let _ = self.bullets.push((self.triangle_x, self.triangle_y - 4));
-            self.bullet_cooldown = 10; // Spawn every 10 frames
+        } else if firing {
+            let _ = self
+                .bullets
+                .push((self.triangle_x.round(), self.triangle_y - 4));
+            self.bullet_cooldown = match fire_mode {
+                FireMode::Auto => AUTO_FIRE_COOLDOWN,
+                FireMode::Manual => MANUAL_FIRE_COOLDOWN,
+            };
             needs_redraw = true;
         }
 
@@ -242,29 +756,34 @@ impl App {
 
         if self.asteroid_cooldown > 0 {
             self.asteroid_cooldown -= 1;
-        } else {
-            // Use frame_count for pseudo-random positioning
-            let x = ((self.frame_count * 17 + 13) % 108) as i32 + 10; // Between 10 and 118
-            let radius = ((self.frame_count * 7) % 3 + 3) as u32; // Radius between 3 and 5
-            let seed = self
-                .frame_count
-                .wrapping_mul(1103515245)
-                .wrapping_add(12345);
-            let _ = self.asteroids.push(Asteroid {
-                x,
+        } else if asteroid::can_spawn(self.asteroids.len(), self.asteroids.capacity()) {
+            // Leaves room for both fragments of the next shot, see `asteroid::SPAWN_RESERVE`
+            let x = self.rng.range(10, self.width - 10);
+            let radius = self.rng.range(
+                asteroid::SMALL_RADIUS as i32,
+                asteroid::LARGE_RADIUS as i32 + 1,
+            ) as u32;
+            let shape = AsteroidShape::generate(radius, self.rng.next_u32());
+            let spawned = self.asteroids.push(Asteroid {
+                x: Fixed::from_int(x),
                 y: -10,
+                vx: Fixed::ZERO,
                 radius,
-                seed,
+                shape,
             });
+            debug_assert!(spawned.is_ok(), "checked for a free slot above");
             self.asteroid_cooldown = 40; // Spawn every ~1.3 seconds at 30fps
             needs_redraw = true;
         }
 
         let mut i = 0;
         while i < self.asteroids.len() {
-            self.asteroids[i].y += 1;
+            let asteroid = &mut self.asteroids[i];
+            asteroid.y += 1;
+            asteroid.x += asteroid.vx;
 
-            if self.asteroids[i].y > 70 {
+            let x = asteroid.x.round();
+            if asteroid.y > self.height + 6 || x < -6 || x > self.width + 6 {
                 self.asteroids.swap_remove(i);
             } else {
                 i += 1;
@@ -272,6 +791,31 @@ impl App {
             needs_redraw = true;
         }
 
+        if !self.starfield.is_empty() {
+            self.starfield.update(&mut self.rng);
+            needs_redraw = true;
+        }
+
+        // Engine trail on every other frame, spawned before updating so it starts moving at once
+        if self.frame_count % 2 == 0 {
+            self.particles
+                .exhaust(&mut self.rng, self.triangle_x.round(), self.triangle_y + 5);
+        }
+        self.particles.update();
+        if !self.particles.is_empty() {
+            needs_redraw = true;
+        }
+
+        let mut i = 0;
+        while i < self.explosions.len() {
+            if self.explosions[i].2.advance(&sprite::EXPLOSION) {
+                i += 1;
+            } else {
+                self.explosions.swap_remove(i);
+            }
+            needs_redraw = true;
+        }
+
         // Check collisions between bullets and asteroids
         let mut bullet_idx = 0;
         while bullet_idx < self.bullets.len() {
@@ -280,25 +824,19 @@ impl App {
             let mut asteroid_idx = 0;
 
             while asteroid_idx < self.asteroids.len() {
-                let asteroid = &self.asteroids[asteroid_idx];
-
-                // Simple distance-based collision detection
-                let dx = bx - asteroid.x;
-                let dy = by - asteroid.y;
-                let dist_sq = dx * dx + dy * dy;
-                let collision_dist = (asteroid.radius as i32 + 2) * (asteroid.radius as i32 + 2); // radius + bullet size
-
-                if dist_sq < collision_dist {
-                    self.asteroids.swap_remove(asteroid_idx);
-                    self.score += 1;
-                    if self.score > self.high_score {
-                        self.high_score = self.score;
-                        if let Err(e) = storage::save_high_score(self.high_score, &mut self.flash) {
-                            println!("Failed to save high score: {:?}", e);
-                        } else {
-                            println!("New high score saved: {}", self.high_score);
-                        }
-                    }
+                if self.bullet_hits(bx, by, &self.asteroids[asteroid_idx]) {
+                    let asteroid = self.asteroids.swap_remove(asteroid_idx);
+                    let x = asteroid.x.round();
+                    self.particles
+                        .burst(&mut self.rng, x, asteroid.y, &ASTEROID_DEBRIS);
+                    // Skipping the effect when all slots are busy is harmless
+                    let _ = self.explosions.push((
+                        x,
+                        asteroid.y,
+                        Animation::new(EXPLOSION_FRAME_TICKS),
+                    ));
+                    self.split_asteroid(&asteroid);
+                    self.score += asteroid::points(asteroid.radius);
                     hit = true;
                     needs_redraw = true;
                     break;
@@ -317,201 +855,1161 @@ impl App {
         // Check collisions between asteroids and triangle
         let mut i = 0;
         while i < self.asteroids.len() {
-            let asteroid = &self.asteroids[i];
-
-            let dx = asteroid.x - self.triangle_x;
-            let dy = asteroid.y - self.triangle_y;
-            let dist_sq = dx * dx + dy * dy;
-            let collision_dist = (asteroid.radius as i32 + 4) * (asteroid.radius as i32 + 4); // radius + triangle size
-
-            if dist_sq < collision_dist {
+            if self.ship_hit_by(&self.asteroids[i]) {
+                if !demo {
+                    println!("Hit by asteroid! Score reset to 0");
+                    self.record_score();
+                    self.game_over = Some((self.score, Instant::now()));
+                }
                 self.score = 0;
                 self.asteroids.swap_remove(i);
+                self.particles.burst(
+                    &mut self.rng,
+                    self.triangle_x.round(),
+                    self.triangle_y,
+                    &SHIP_DEBRIS,
+                );
+                self.effects.shake(HIT_SHAKE_FRAMES, HIT_SHAKE_STRENGTH);
+                self.effects.flash(HIT_FLASH_FRAMES);
+                self.effects
+                    .slow_motion(HIT_SLOW_MOTION_FRAMES, HIT_SLOW_MOTION_FACTOR);
                 needs_redraw = true;
-                println!("Hit by asteroid! Score reset to 0");
             } else {
                 i += 1;
             }
         }
 
-        if needs_redraw || show_reset_warning {
+        needs_redraw
+    }
+
+    /// Runs the idle title screen, cycling through the attract mode pages.
+    /// Any button press starts a new game.
+    fn title_loop(&mut self, state: &State, prev_state: &State) {
+        if state.has_input() && !prev_state.has_input() {
+            println!("New game");
+            self.restart_round();
+            self.scene = Scene::Playing;
+            self.effects.start_transition(Transition::Iris);
             self.render();
+            return;
         }
+
+        if self.attract_page_start.elapsed() >= Duration::from_secs(self.attract_page.seconds()) {
+            self.attract_page = self.attract_page.next();
+            self.attract_page_start = Instant::now();
+            self.effects.start_transition(Transition::Wipe);
+            if self.attract_page == Page::Demo {
+                self.restart_round();
+                self.pilot = Pilot::new();
+            }
+        }
+
+        if self.attract_page == Page::Demo {
+            let threats: heapless::Vec<Threat, MAX_ASTEROIDS> = self
+                .asteroids
+                .iter()
+                .map(|asteroid| Threat {
+                    x: asteroid.x.round(),
+                    y: asteroid.y,
+                    radius: asteroid.radius,
+                })
+                .collect();
+            let input = self.pilot.steer(
+                self.triangle_x.round(),
+                self.triangle_y,
+                (SHIP_EDGE_MARGIN, self.width - SHIP_EDGE_MARGIN),
+                &threats,
+            );
+            self.play_frame(&input);
+        } else if !self.starfield.is_empty() {
+            self.starfield.update(&mut self.rng);
+        }
+
+        // Something moves on every page, if only the prompt blinking
+        self.render();
     }
 
-    /// Draw an asteroid with an irregular shape using individual pixels
-    fn draw_asteroid(&mut self, x: i32, y: i32, radius: u32, seed: u32) {
-        let r = radius as i32;
-
-        // Draw an irregular asteroid using circle points with pseudo-random variations
-        // Using Bresenham-like approach with 8 octants for efficiency
-        let mut oct_x = r;
-        let mut oct_y = 0;
-        let mut decision = 1 - r;
-
-        while oct_x >= oct_y {
-            // For each of the 8 octants, draw with pseudo-random variation
-            let points = [
-                (oct_x, oct_y),
-                (oct_y, oct_x),
-                (-oct_x, oct_y),
-                (-oct_y, oct_x),
-                (-oct_x, -oct_y),
-                (-oct_y, -oct_x),
-                (oct_x, -oct_y),
-                (oct_y, -oct_x),
-            ];
-
-            for (i, &(dx, dy)) in points.iter().enumerate() {
-                // Create pseudo-random variation for each point
-                let point_seed = seed
-                    .wrapping_add((dx + dy * 256 + i as i32 * 17) as u32)
-                    .wrapping_mul(1103515245)
-                    .wrapping_add(12345);
-                let variation = ((point_seed >> 16) % 3) as i32 - 1;
-
-                let px = x + dx + variation;
-                let py = y + dy + variation;
-
-                // Draw pixel cluster for rocky look
-                for pdx in -1..=1 {
-                    for pdy in -1..=1 {
-                        let final_x = px + pdx;
-                        let final_y = py + pdy;
-
-                        if final_x >= 0 && final_x < 128 && final_y >= 0 && final_y < 64 {
-                            if pdx * pdx + pdy * pdy <= 1 {
-                                self.display.set_pixel(final_x as u32, final_y as u32, true);
-                            }
-                        }
-                    }
+    /// Ends the game in progress and shows the title screen
+    fn show_title(&mut self) {
+        self.record_score();
+        self.restart_round();
+        self.scene = Scene::Title;
+        self.attract_page = Page::Title;
+        self.attract_page_start = Instant::now();
+        self.effects.start_transition(Transition::Wipe);
+        self.render();
+    }
+
+    /// Enters the score of the game that just ended on the leaderboard
+    fn record_score(&mut self) {
+        let Some(place) = self.leaderboard.insert(self.score) else {
+            return;
+        };
+        if let Err(e) = storage::save_leaderboard(&self.leaderboard, &mut self.flash) {
+            println!("Failed to save leaderboard: {:?}", e);
+        } else if place == 0 {
+            println!("New high score saved: {}", self.score);
+        } else {
+            println!("Score {} saved in place {}", self.score, place + 1);
+        }
+    }
+
+    /// Best score so far, including the game in progress
+    fn high_score(&self) -> u32 {
+        self.leaderboard.best().max(self.score)
+    }
+
+    /// Replaces a shot asteroid with its fragments. A fragment without a free slot, which
+    /// takes a second split before any asteroid leaves, is left out and counted in the
+    /// `stats` output.
+    fn split_asteroid(&mut self, asteroid: &Asteroid) {
+        let Some(fragments) = asteroid.fragments(&mut self.rng) else {
+            return;
+        };
+        for fragment in fragments {
+            if self.asteroids.push(fragment).is_err() {
+                self.dropped_fragments += 1;
+            }
+        }
+    }
+
+    /// Whether a bullet at `bx`, `by` hits an asteroid, according to the hitbox setting
+    fn bullet_hits(&self, bx: i32, by: i32, asteroid: &Asteroid) -> bool {
+        match self.settings.hitbox {
+            Hitbox::Circle => {
+                let dx = bx - asteroid.x.round();
+                let dy = by - asteroid.y;
+                let collision_dist = asteroid.radius as i32 + 2; // radius + bullet size
+                dx * dx + dy * dy < collision_dist * collision_dist
+            }
+            Hitbox::Pixel => asteroid
+                .shape
+                .mask(asteroid.x.round(), asteroid.y)
+                .overlaps(&collision::bullet_mask(bx, by)),
+        }
+    }
+
+    /// Whether an asteroid hits the ship, according to the hitbox setting
+    fn ship_hit_by(&self, asteroid: &Asteroid) -> bool {
+        let ship_x = self.triangle_x.round();
+        match self.settings.hitbox {
+            Hitbox::Circle => {
+                let dx = asteroid.x.round() - ship_x;
+                let dy = asteroid.y - self.triangle_y;
+                let collision_dist = asteroid.radius as i32 + 4; // radius + triangle size
+                dx * dx + dy * dy < collision_dist * collision_dist
+            }
+            Hitbox::Pixel => {
+                let ship = collision::centered_mask(
+                    ship_x,
+                    self.triangle_y,
+                    sprite::SHIP.size(),
+                    &self.ship_rows,
+                );
+                asteroid
+                    .shape
+                    .mask(asteroid.x.round(), asteroid.y)
+                    .overlaps(&ship)
+            }
+        }
+    }
+
+    /// Shifts the frame now and then and runs the screensaver before sleep. Returns true
+    /// if the screensaver took this frame, including the input that ends it.
+    fn burn_in_guard(&mut self, idle: Duration, has_input: bool) -> bool {
+        let guard = self.settings.burn_in == BurnInGuard::On;
+
+        if guard && self.shift_start.elapsed() >= SHIFT_PERIOD {
+            self.shift_index = (self.shift_index + 1) % SHIFT_PATTERN.len();
+            self.shift_start = Instant::now();
+        }
+
+        let sleep_ms = self.sleep_timeout.as_millis();
+        let saver_ms = SCREENSAVER_TIME.as_millis().min(sleep_ms / 2);
+        if guard && !has_input && sleep_ms > 0 && idle.as_millis() >= sleep_ms - saver_ms {
+            self.screensaver_frame();
+            return true;
+        }
+        if self.screensaver {
+            // The input only ends the screensaver, like waking from sleep
+            self.screensaver = false;
+            self.render();
+            return true;
+        }
+        false
+    }
+
+    /// Advances the screensaver: the starfield with the title bouncing around
+    fn screensaver_frame(&mut self) {
+        if !self.screensaver {
+            println!("Screensaver on");
+            self.screensaver = true;
+            self.saver_position = Point::new(self.width / 4, self.height / 3);
+        }
+
+        if !self.starfield.is_empty() {
+            self.starfield.update(&mut self.rng);
+        }
+
+        // Half speed is plenty and halves the pixels changing each frame
+        self.frame_count = self.frame_count.wrapping_add(1);
+        if self.frame_count % 2 == 0 {
+            let size = self.font().character_size;
+            let max_x = self.width - SCREENSAVER_TEXT.len() as i32 * size.width as i32;
+            let max_y = self.height - size.height as i32;
+            let next = self.saver_position + self.saver_direction;
+            if next.x < 0 || next.x > max_x {
+                self.saver_direction.x = -self.saver_direction.x;
+            }
+            if next.y < 0 || next.y > max_y {
+                self.saver_direction.y = -self.saver_direction.y;
+            }
+            self.saver_position += self.saver_direction;
+        }
+
+        self.render();
+    }
+
+    /// Turns the display off and drops to 4 fps until the next input
+    fn enter_sleep(&mut self) {
+        println!("Entering sleep mode (display off, checking inputs at 4 fps)");
+        self.is_sleeping = true;
+        self.screensaver = false;
+        // Nothing would count the effects down while asleep
+        self.effects = Effects::new();
+        self.presenter.set_inverted(false);
+        self.presenter.set_display_on(false);
+    }
+
+    fn wake(&mut self) {
+        println!("Waking from sleep");
+        self.is_sleeping = false;
+        self.is_deep_sleeping = false;
+        self.last_input_time = Instant::now();
+        self.presenter.set_display_on(true);
+        self.render();
+    }
+
+    /// Sends the contrast setting to the panel, lowered while dimmed
+    fn apply_contrast(&mut self) {
+        let contrast = self.settings.contrast.value();
+        self.presenter.set_contrast(if self.dimmed {
+            contrast.min(DIM_CONTRAST)
+        } else {
+            contrast
+        });
+    }
+
+    /// Last idle stage, called between frames while in sleep mode. Light sleeps until a
+    /// button is pressed or it's time to check the other inputs.
+    fn deep_sleep(&mut self) {
+        if !self.is_deep_sleeping {
+            println!("Entering deep sleep (light sleep between input checks)");
+            self.is_deep_sleeping = true;
+        }
+        let buttons = GpioWakeupSource::new();
+        let timer = TimerWakeupSource::new(LIGHT_SLEEP_CHECK);
+        // The display task on the APP core must not be mid-transfer or holding the frame
+        // lock while the chip sleeps
+        self.presenter.suspend();
+        self.rtc.sleep_light(&[&buttons, &timer]);
+        self.presenter.resume();
+    }
+
+    /// Clears the leaderboard in memory and flash
+    fn reset_high_score(&mut self) {
+        self.leaderboard.clear();
+        if let Err(e) = storage::save_leaderboard(&self.leaderboard, &mut self.flash) {
+            println!("Failed to clear high scores: {:?}", e);
+        } else {
+            println!("High scores cleared!");
+        }
+    }
+
+    /// Runs a command received on the serial console
+    fn execute(&mut self, command: Command) {
+        match command {
+            Command::Help => println!("{}", command::HELP),
+            Command::Score => {
+                println!("Score: {}  High score: {}", self.score, self.high_score());
+                for (place, score) in self.leaderboard.scores().iter().enumerate() {
+                    println!("{}. {}", place + 1, score);
+                }
+            }
+            Command::ResetScores => {
+                self.score = 0;
+                self.reset_high_score();
+            }
+            Command::SetFps(fps) => {
+                self.target_fps = fps;
+                self.frame_duration = Duration::from_millis((1000 / fps) as u64);
+                println!("Target framerate: {} fps", fps);
+            }
+            Command::SetFireMode(mode) => {
+                self.settings.fire_mode = mode;
+                if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+                    println!("Failed to save settings: {:?}", e);
+                }
+                println!("Fire mode: {}", self.fire_mode().label());
+            }
+            Command::SetStars(density) => {
+                self.set_star_density(density);
+                if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+                    println!("Failed to save settings: {:?}", e);
+                }
+                println!("Stars: {}", density.label());
+            }
+            Command::Sleep => {
+                if !self.is_sleeping {
+                    self.enter_sleep();
+                }
+            }
+            Command::SetRotation(rotation) => {
+                self.set_rotation(rotation);
+                if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+                    println!("Failed to save settings: {:?}", e);
+                }
+                println!(
+                    "Rotation: {} ({}x{})",
+                    rotation.label(),
+                    self.width,
+                    self.height
+                );
+            }
+            Command::SetHitbox(hitbox) => {
+                self.settings.hitbox = hitbox;
+                if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+                    println!("Failed to save settings: {:?}", e);
+                }
+                println!("Hitbox: {}", hitbox.label());
+            }
+            Command::SetBurnIn(burn_in) => {
+                self.set_burn_in(burn_in);
+                if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+                    println!("Failed to save settings: {:?}", e);
+                }
+                println!("Burn-in guard: {}", burn_in.label());
+            }
+            Command::SetContrast(contrast) => {
+                self.settings.contrast = contrast;
+                self.apply_contrast();
+                if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+                    println!("Failed to save settings: {:?}", e);
+                }
+                println!("Contrast: {}", contrast.label());
+            }
+            Command::SetLanguage(language) => {
+                self.settings.language = language;
+                if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+                    println!("Failed to save settings: {:?}", e);
                 }
+                println!("Language: {}", language.label());
+                self.render();
+            }
+            #[cfg(feature = "debug-overlay")]
+            Command::Debug => {
+                self.overlay.toggle();
+                println!("Debug overlay: {}", self.overlay.is_visible());
+                self.render();
+            }
+            #[cfg(not(feature = "debug-overlay"))]
+            Command::Debug => println!("Built without the debug-overlay feature"),
+            Command::Seed(seed) => {
+                // Restart the round so everything from here on is reproducible
+                self.rng = Rng::new(seed);
+                self.restart_round();
+                println!("Round restarted with seed {}", seed);
             }
+            Command::Stats => {
+                println!("Frame: {}", self.frame_count);
+                println!("Target framerate: {} fps", self.target_fps);
+                println!(
+                    "Bullets: {}/{}",
+                    self.bullets.len(),
+                    self.bullets.capacity()
+                );
+                println!(
+                    "Asteroids: {}/{} ({} fragments dropped)",
+                    self.asteroids.len(),
+                    self.asteroids.capacity(),
+                    self.dropped_fragments
+                );
+                println!("Score: {}  High score: {}", self.score, self.high_score());
+                println!("Fire mode: {}", self.fire_mode().label());
+                println!("Sleeping: {}", self.is_sleeping);
+
+                if self.timed_frames > 0 {
+                    let budget = self.frame_duration.as_micros();
+                    let frame_avg = self.frame_time_total / self.timed_frames as u64;
+                    let stars_avg = self.starfield_time_total / self.timed_frames as u64;
+                    println!(
+                        "Frame work: {} us avg ({}% of {} us budget)",
+                        frame_avg,
+                        frame_avg * 100 / budget,
+                        budget
+                    );
+                    println!(
+                        "Starfield: {} us avg ({}% of budget)",
+                        stars_avg,
+                        stars_avg * 100 / budget
+                    );
+                }
 
-            oct_y += 1;
-            if decision < 0 {
-                decision += 2 * oct_y + 1;
-            } else {
-                oct_x -= 1;
-                decision += 2 * (oct_y - oct_x) + 1;
+                let display = self.presenter.take_stats();
+                if display.frames > 0 {
+                    println!(
+                        "Display: {} frames sent, {} skipped, {} bytes avg",
+                        display.frames,
+                        display.skipped,
+                        display.bytes / display.frames
+                    );
+                }
+                self.frame_time_total = 0;
+                self.starfield_time_total = 0;
+                self.timed_frames = 0;
+            }
+        }
+    }
+
+    /// Clears everything in play and starts the round over from the bottom center
+    fn restart_round(&mut self) {
+        self.asteroids.clear();
+        self.bullets.clear();
+        self.explosions.clear();
+        self.particles.clear();
+        self.asteroid_cooldown = 30;
+        self.bullet_cooldown = 0;
+        self.score = 0;
+        self.game_over = None;
+        self.triangle_x = Fixed::from_int(self.width / 2);
+        self.triangle_vx = Fixed::ZERO;
+        self.triangle_y = self.height - 6;
+    }
+
+    /// Rotates the playfield, the round restarts since every position changes meaning
+    fn set_rotation(&mut self, rotation: Rotation) {
+        self.settings.rotation = rotation;
+        self.frame.set_rotation(rotation);
+        let size = self.frame.size();
+        self.width = size.width as i32;
+        self.height = size.height as i32;
+        self.starfield = Starfield::new(
+            &mut self.rng,
+            self.settings.star_density.star_count(),
+            self.width as u32,
+            self.height as u32,
+        );
+        self.restart_round();
+    }
+
+    fn set_star_density(&mut self, density: StarDensity) {
+        self.settings.star_density = density;
+        self.starfield
+            .set_count(&mut self.rng, density.star_count());
+    }
+
+    /// Turning the guard off puts the frame back where it belongs
+    fn set_burn_in(&mut self, burn_in: BurnInGuard) {
+        self.settings.burn_in = burn_in;
+        if burn_in == BurnInGuard::Off {
+            self.shift_index = 0;
+        }
+    }
+
+    /// Fire mode in effect, boards without a fire input always use autofire
+    fn fire_mode(&self) -> FireMode {
+        if self.has_fire_input {
+            self.settings.fire_mode
+        } else {
+            FireMode::Auto
+        }
+    }
+
+    /// Shows the settings screen from the top
+    fn open_settings(&mut self) {
+        self.scene = Scene::Settings;
+        self.nav = Nav::new();
+        self.settings_menu = Menu::new(SettingsEntry::ALL.len());
+        self.dialog = None;
+        self.effects.start_transition(Transition::Wipe);
+    }
+
+    /// Saves the settings and goes back to the game
+    fn close_settings(&mut self) {
+        if let Err(e) = storage::save_settings(&self.settings, &mut self.flash) {
+            println!("Failed to save settings: {:?}", e);
+        } else {
+            println!("Settings saved");
+        }
+        self.scene = Scene::Playing;
+        self.bullet_cooldown = 0;
+        self.effects.start_transition(Transition::Wipe);
+    }
+
+    /// Button navigation on the menu screens, fire works like right
+    fn navigate(&mut self, state: &State) -> Option<NavEvent> {
+        let now_ms = Instant::now().duration_since_epoch().as_millis();
+        self.nav.update(
+            state.button_left,
+            state.button_right || state.button_fire,
+            now_ms,
+        )
+    }
+
+    /// Asks a yes/no question over the current screen
+    fn open_dialog(&mut self, confirm: Confirm, question: Message) {
+        let dialog = Dialog::new(
+            self.text(question),
+            self.text(Message::Yes),
+            self.text(Message::No),
+        );
+        self.dialog = Some((confirm, dialog));
+    }
+
+    /// Passes navigation to the open dialog, carrying out what it asked once confirmed
+    fn answer_dialog(&mut self, event: NavEvent) {
+        let Some((confirm, dialog)) = &mut self.dialog else {
+            return;
+        };
+        let confirm = *confirm;
+        let Some(answer) = dialog.update(event) else {
+            return;
+        };
+        self.dialog = None;
+        if !answer {
+            return;
+        }
+        match confirm {
+            Confirm::ResetScores => self.reset_high_score(),
+            Confirm::ClearCrashLog => {
+                if let Err(e) = storage::clear_crash_log(&mut self.flash) {
+                    println!("Failed to clear crash log: {:?}", e);
+                } else {
+                    println!("Crash log cleared");
+                    self.crash_log = None;
+                    self.log_scroll = 0;
+                }
+            }
+        }
+    }
+
+    /// Handles input on the settings screen.
+    /// Left moves to the next entry, right (or fire) changes the selected entry and
+    /// holding it saves and goes back to the game.
+    fn settings_loop(&mut self, state: &State) {
+        let event = self.navigate(state);
+
+        match event {
+            _ if self.dialog.is_some() => {
+                if let Some(event) = event {
+                    self.answer_dialog(event);
+                }
             }
+            Some(NavEvent::Next) => self.settings_menu.next(),
+            Some(NavEvent::Back) => self.close_settings(),
+            Some(NavEvent::Select) => match SettingsEntry::ALL[self.settings_menu.cursor()] {
+                SettingsEntry::Fire => {
+                    if self.has_fire_input {
+                        self.settings.fire_mode = match self.settings.fire_mode {
+                            FireMode::Auto => FireMode::Manual,
+                            FireMode::Manual => FireMode::Auto,
+                        };
+                    } else {
+                        println!("Manual fire needs a fire button or encoder switch");
+                    }
+                }
+                SettingsEntry::Stars => self.set_star_density(self.settings.star_density.next()),
+                SettingsEntry::Rotation => self.set_rotation(self.settings.rotation.next()),
+                SettingsEntry::Hitbox => {
+                    self.settings.hitbox = match self.settings.hitbox {
+                        Hitbox::Circle => Hitbox::Pixel,
+                        Hitbox::Pixel => Hitbox::Circle,
+                    };
+                }
+                SettingsEntry::BurnIn => self.set_burn_in(match self.settings.burn_in {
+                    BurnInGuard::Off => BurnInGuard::On,
+                    BurnInGuard::On => BurnInGuard::Off,
+                }),
+                SettingsEntry::Contrast => {
+                    self.settings.contrast = self.settings.contrast.next();
+                    self.apply_contrast();
+                }
+                SettingsEntry::Language => self.settings.language = self.settings.language.next(),
+                SettingsEntry::Diagnostics => {
+                    self.scene = Scene::Diagnostics;
+                    self.nav = Nav::new();
+                    self.log_scroll = 0;
+                    self.effects.start_transition(Transition::Wipe);
+                }
+                SettingsEntry::ResetScores => {
+                    self.open_dialog(Confirm::ResetScores, Message::ResetScoresQuestion)
+                }
+                SettingsEntry::Back => self.close_settings(),
+            },
+            None => {}
+        }
+
+        if event.is_some() || self.effects.is_animating() {
+            self.render();
+        }
+    }
+
+    /// Handles input on the diagnostics screen.
+    /// Left scrolls the crash log, right (or fire) offers to clear it and holding it goes
+    /// back to the settings.
+    fn diagnostics_loop(&mut self, state: &State) {
+        let event = self.navigate(state);
+
+        match event {
+            _ if self.dialog.is_some() => {
+                if let Some(event) = event {
+                    self.answer_dialog(event);
+                }
+            }
+            Some(NavEvent::Next) => {
+                // A page at a time, back to the top after the last one
+                let (columns, rows) = self.log_layout();
+                let lines = self
+                    .crash_log
+                    .as_ref()
+                    .map_or(0, |log| crash::wrap(log, columns).count());
+                self.log_scroll += rows.saturating_sub(1).max(1);
+                if self.log_scroll >= lines {
+                    self.log_scroll = 0;
+                }
+            }
+            Some(NavEvent::Select) => {
+                if self.crash_log.is_some() {
+                    self.open_dialog(Confirm::ClearCrashLog, Message::ClearLogQuestion);
+                }
+            }
+            Some(NavEvent::Back) => {
+                self.scene = Scene::Settings;
+                self.nav = Nav::new();
+                self.effects.start_transition(Transition::Wipe);
+            }
+            None => {}
+        }
+
+        if event.is_some() || self.effects.is_animating() {
+            self.render();
+        }
+    }
+
+    /// Columns and rows of crash log text that fit on the diagnostics screen
+    fn log_layout(&self) -> (usize, usize) {
+        let font = self.font();
+        let columns = ((self.width - 4) / font.character_size.width as i32) as usize;
+        // The first row below the title is taken by the heading
+        let rows = Menu::visible_rows(self.height, font).saturating_sub(1);
+        (columns, rows)
+    }
+
+    /// On-screen text in the language picked in the settings
+    fn text(&self, id: Message) -> &'static str {
+        strings::text(self.settings.language, id)
+    }
+
+    /// Font for all text, portrait playfields are too narrow for the regular one
+    fn font(&self) -> &'static MonoFont<'static> {
+        if self.width >= 128 {
+            &FONT_6X10
+        } else {
+            &FONT_4X6
+        }
+    }
+
+    /// How an entry of the settings screen shows up, with its current value
+    fn settings_item(&self, entry: SettingsEntry) -> Item<'static> {
+        let settings = self.settings;
+        match entry {
+            SettingsEntry::Fire => Item::Choice {
+                label: self.text(Message::Fire),
+                value: self.text(match self.fire_mode() {
+                    FireMode::Auto => Message::Auto,
+                    FireMode::Manual => Message::Manual,
+                }),
+            },
+            SettingsEntry::Stars => Item::Slider {
+                label: self.text(Message::Stars),
+                value: settings.star_density as u8,
+                max: StarDensity::High as u8,
+            },
+            SettingsEntry::Rotation => Item::Choice {
+                label: self.text(Message::Rotation),
+                value: settings.rotation.label(),
+            },
+            SettingsEntry::Hitbox => Item::Choice {
+                label: self.text(Message::Hitbox),
+                value: self.text(match settings.hitbox {
+                    Hitbox::Circle => Message::Circle,
+                    Hitbox::Pixel => Message::Pixel,
+                }),
+            },
+            SettingsEntry::BurnIn => Item::Toggle {
+                label: self.text(Message::BurnIn),
+                on: settings.burn_in == BurnInGuard::On,
+            },
+            SettingsEntry::Contrast => Item::Slider {
+                label: self.text(Message::Contrast),
+                value: settings.contrast as u8,
+                max: Contrast::Max as u8,
+            },
+            SettingsEntry::Language => Item::Choice {
+                label: self.text(Message::Language),
+                value: settings.language.code(),
+            },
+            SettingsEntry::Diagnostics => Item::Action(self.text(Message::Diagnostics)),
+            SettingsEntry::ResetScores => Item::Action(self.text(Message::ResetScores)),
+            SettingsEntry::Back => Item::Action(self.text(Message::Back)),
+        }
+    }
+
+    /// Renders the settings screen with a cursor on the selected entry
+    fn render_settings(&mut self) {
+        self.frame.clear(BinaryColor::Off).unwrap();
+
+        let items = SettingsEntry::ALL.map(|entry| self.settings_item(entry));
+        let font = self.font();
+        let title = self.text(Message::Settings);
+        self.settings_menu
+            .draw(&mut self.frame, font, title, &items)
+            .unwrap();
+        // Marks the screen as settings at a glance, level with the title
+        let icon = sprite::ICON_GEAR.size();
+        let corner = Point::new(
+            self.width - icon.width as i32 / 2 - 2,
+            font.character_size.height as i32 / 2,
+        );
+        sprite::ICON_GEAR.draw(0, corner, &mut self.frame).unwrap();
+        if let Some((_, dialog)) = &self.dialog {
+            dialog.draw(&mut self.frame, font).unwrap();
+        }
+
+        self.present_scene();
+    }
+
+    /// Renders the diagnostics screen with the crash log wrapped to the screen width
+    fn render_diagnostics(&mut self) {
+        self.frame.clear(BinaryColor::Off).unwrap();
+
+        let font = self.font();
+        let line = ui::line_height(font);
+        let title_y = font.baseline as i32 + 1;
+        let title = self.text(Message::Diagnostics);
+        ui::draw_text(&mut self.frame, title, font, Align::Left, title_y).unwrap();
+
+        // The crash log is printed in full at boot, the screen shows a page of it
+        let (columns, rows) = self.log_layout();
+        let (heading, log) = match &self.crash_log {
+            Some(log) => (self.text(Message::LastCrash), log.as_str()),
+            None => (self.text(Message::NoCrash), ""),
+        };
+        ui::draw_text(&mut self.frame, heading, font, Align::Left, title_y + line).unwrap();
+        let lines = crash::wrap(log, columns).skip(self.log_scroll);
+        for (row, text) in lines.take(rows).enumerate() {
+            let y = title_y + (row as i32 + 2) * line;
+            ui::draw_text(&mut self.frame, text, font, Align::Left, y).unwrap();
         }
+        if let Some((_, dialog)) = &self.dialog {
+            dialog.draw(&mut self.frame, font).unwrap();
+        }
+
+        self.present_scene();
+    }
+
+    /// Draws the starfield, farther layers dimmer with dithering on
+    fn draw_stars(&mut self) {
+        let (frame, dither) = (&mut self.frame, self.dither);
+        self.starfield.draw(|x, y, layer| {
+            if dither.is_point_lit(STAR_GRAYS[layer], x as i32, y as i32) {
+                frame.set_pixel(x, y, true);
+            }
+        });
+    }
 
-        // Fill interior with scattered pixels for texture
-        for i in 0..(r * 3) {
-            let pixel_seed = seed
-                .wrapping_add((i * 13) as u32)
-                .wrapping_mul(1103515245)
-                .wrapping_add(12345);
-            let offset_x = ((pixel_seed >> 8) % (r as u32 * 2)) as i32 - r;
-            let offset_y = ((pixel_seed >> 16) % (r as u32 * 2)) as i32 - r;
+    /// Renders the title screen pages of the attract mode over the starfield
+    fn render_attract(&mut self) {
+        self.frame.clear(BinaryColor::Off).unwrap();
+        self.draw_stars();
 
-            let px = x + offset_x;
-            let py = y + offset_y;
+        let font = self.font();
+        let line_height = font.character_size.height as i32 + 1;
+        // Rows below the heading, short panels show fewer
+        let rows = ((self.height - 9) / line_height).max(0) as usize;
 
-            let dist_sq = offset_x * offset_x + offset_y * offset_y;
-            if dist_sq < (r - 1) * (r - 1) && px >= 0 && px < 128 && py >= 0 && py < 64 {
-                self.display.set_pixel(px as u32, py as u32, true);
+        use core::fmt::Write;
+        match self.attract_page {
+            Page::Title | Page::Demo => {
+                self.draw_centered("ESP ASTEROIDS", self.height / 2 - line_height / 2);
+                // Blink the prompt, once a second
+                if self.attract_page_start.elapsed().as_millis() % 1000 < 500 {
+                    let prompt = self.text(Message::PressAnyButton);
+                    self.draw_centered(prompt, self.height / 2 + line_height);
+                }
+            }
+            Page::Leaderboard => {
+                self.draw_centered(self.text(Message::HighScores), 8);
+                // Places on the left and scores on the right of a centered column
+                let column = ui::text_width("5. 000000", font);
+                let left = (self.width - column) / 2;
+                let scores = *self.leaderboard.scores();
+                for (place, score) in scores.into_iter().enumerate().take(rows) {
+                    let y = 8 + (place as i32 + 1) * line_height;
+                    let mut text: heapless::String<16> = heapless::String::new();
+                    write!(&mut text, "{}.", place + 1).unwrap();
+                    ui::draw_text_in(&mut self.frame, &text, font, Align::Left, left, column, y)
+                        .unwrap();
+                    text.clear();
+                    if score > 0 {
+                        write!(&mut text, "{}", score).unwrap();
+                    } else {
+                        text.push('-').unwrap();
+                    }
+                    ui::draw_text_in(&mut self.frame, &text, font, Align::Right, left, column, y)
+                        .unwrap();
+
+                    // A trophy in front of the best score, where the margin has room for it
+                    let icon = sprite::ICON_TROPHY.size();
+                    if place == 0 && score > 0 && left >= icon.width as i32 + 2 {
+                        let center = Point::new(
+                            left - icon.width as i32 / 2 - 2,
+                            y - font.baseline as i32 / 2,
+                        );
+                        sprite::ICON_TROPHY
+                            .draw(0, center, &mut self.frame)
+                            .unwrap();
+                    }
+                }
+            }
+            Page::Credits => {
+                self.draw_centered("ESP Asteroids", 8);
+                let credits = [Message::Credits1, Message::Credits2, Message::Credits3];
+                for (row, line) in credits.iter().enumerate().take(rows) {
+                    self.draw_centered(self.text(*line), 8 + (row as i32 + 1) * line_height);
+                }
             }
         }
+
+        self.present_scene();
+    }
+
+    /// Blanks what the scene transition hasn't uncovered yet and presents the frame
+    fn present_scene(&mut self) {
+        self.effects.draw_transition(&mut self.frame).unwrap();
+        self.presenter.present(&self.frame);
+    }
+
+    /// Draws a line of text horizontally centered with its baseline at `y`
+    fn draw_centered(&mut self, text: &str, y: i32) {
+        let font = self.font();
+        ui::draw_text(&mut self.frame, text, font, Align::Center, y).unwrap();
+    }
+
+    /// Draws the final score in large digits on a blank box in the middle of the playfield
+    fn draw_game_over(&mut self, score: u32) {
+        use core::fmt::Write;
+        let mut digits: heapless::String<10> = heapless::String::new();
+        write!(&mut digits, "{}", score).unwrap();
+
+        let caption = self.text(Message::GameOver);
+        let big = &fonts::FONT_DIGITS_8X12;
+        let height = HUD_FONT.character_size.height + 3 + big.character_size.height;
+        let width = ui::text_width(&digits, big).max(ui::text_width(caption, HUD_FONT)) + 6;
+        let top = (self.height - height as i32) / 2;
+        self.frame
+            .fill_solid(
+                &Rectangle::new(
+                    Point::new((self.width - width) / 2, top - 3),
+                    Size::new(width as u32, height + 6),
+                ),
+                BinaryColor::Off,
+            )
+            .unwrap();
+
+        let caption_y = top + HUD_FONT.baseline as i32;
+        ui::draw_text(&mut self.frame, caption, HUD_FONT, Align::Center, caption_y).unwrap();
+        let digits_y = top + (HUD_FONT.character_size.height + 3 + big.baseline) as i32;
+        ui::draw_text(&mut self.frame, &digits, big, Align::Center, digits_y).unwrap();
+    }
+
+    /// Renders the screensaver over the starfield
+    fn render_screensaver(&mut self) {
+        self.frame.clear(BinaryColor::Off).unwrap();
+        self.draw_stars();
+
+        let text_style = MonoTextStyle::new(self.font(), BinaryColor::On);
+        Text::with_baseline(
+            SCREENSAVER_TEXT,
+            self.saver_position,
+            text_style,
+            Baseline::Top,
+        )
+        .draw(&mut self.frame)
+        .unwrap();
+
+        self.presenter.present(&self.frame);
     }
 
     /// Renders the current frame to the display
     fn render(&mut self) {
-        self.display.clear(BinaryColor::Off).unwrap();
+        // Everything drawn this frame moves by the burn-in shift plus any screen shake
+        let (shift_x, shift_y) = SHIFT_PATTERN[self.shift_index];
+        let (shake_x, shake_y) = self.effects.shake_offset();
+        self.frame.set_offset(shift_x + shake_x, shift_y + shake_y);
+
+        if self.screensaver {
+            self.render_screensaver();
+            return;
+        }
+        if self.scene == Scene::Settings {
+            self.render_settings();
+            return;
+        }
+        if self.scene == Scene::Diagnostics {
+            self.render_diagnostics();
+            return;
+        }
+        if self.scene == Scene::Title && self.attract_page != Page::Demo {
+            self.render_attract();
+            return;
+        }
+
+        #[cfg(feature = "debug-overlay")]
+        let render_start = Instant::now();
+        self.frame.clear(BinaryColor::Off).unwrap();
+
+        // Draw the starfield first so everything else covers it
+        let starfield_start = Instant::now();
+        self.draw_stars();
+        self.starfield_time_total += starfield_start.elapsed().as_micros();
 
         // Draw score in top left
         use core::fmt::Write;
         let mut score_text: heapless::String<16> = heapless::String::new();
         write!(&mut score_text, "{}", self.score).unwrap();
 
-        let text_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
-        Text::new(&score_text, Point::new(2, 8), text_style)
-            .draw(&mut self.display)
-            .unwrap();
+        ui::draw_text(
+            &mut self.frame,
+            &score_text,
+            HUD_FONT,
+            Align::Left,
+            HUD_BASELINE,
+        )
+        .unwrap();
 
         // Draw high score in top right
-        let mut hs_text: heapless::String<16> = heapless::String::new();
-        write!(&mut hs_text, "HS:{}", self.high_score).unwrap();
-
-        // Position text on the right side (128px wide screen, font is 6px wide)
-        let text_width = hs_text.len() as i32 * 6;
-        Text::new(&hs_text, Point::new(128 - text_width - 2, 8), text_style)
-            .draw(&mut self.display)
-            .unwrap();
+        let hs_text: heapless::String<16> = if self.scene == Scene::Title {
+            strings::format(self.settings.language, Message::Demo, &[])
+        } else {
+            strings::format(
+                self.settings.language,
+                Message::HighScore,
+                &[&self.high_score()],
+            )
+        };
+        ui::draw_text(
+            &mut self.frame,
+            &hs_text,
+            HUD_FONT,
+            Align::Right,
+            HUD_BASELINE,
+        )
+        .unwrap();
 
         // Draw warning if both buttons held for 10+ seconds
         if let Some(start_time) = self.both_buttons_held_start {
             let held_duration = start_time.elapsed();
             if held_duration >= Duration::from_secs(10) {
                 let remaining = 15 - held_duration.as_secs();
-                let mut warning_text: heapless::String<32> = heapless::String::new();
-                write!(&mut warning_text, "Score Reset in {}", remaining).unwrap();
-
-                let warning_width = warning_text.len() as i32 * 6;
-                let x_pos = (128 - warning_width) / 2;
-                Text::new(&warning_text, Point::new(x_pos, 32), text_style)
-                    .draw(&mut self.display)
-                    .unwrap();
+                let warning_text: heapless::String<32> =
+                    strings::format(self.settings.language, Message::ScoreResetIn, &[&remaining]);
+                self.draw_centered(&warning_text, self.height / 2);
+            } else if held_duration >= SETTINGS_HOLD {
+                let mut hint_text = self.text(Message::ReleaseForSettings);
+                if ui::text_width(hint_text, self.font()) > self.width {
+                    hint_text = self.text(Message::Settings);
+                }
+                self.draw_centered(hint_text, self.height / 2);
             }
         }
 
-        // Draw asteroids with irregular shapes
-        for i in 0..self.asteroids.len() {
-            let x = self.asteroids[i].x;
-            let y = self.asteroids[i].y;
-            let radius = self.asteroids[i].radius;
-            let seed = self.asteroids[i].seed;
-            self.draw_asteroid(x, y, radius, seed);
+        // Draw asteroids from their pre-rendered shapes, fading in at the top
+        let dither = self.dither;
+        for asteroid in &self.asteroids {
+            let frame = &mut self.frame;
+            let level = match asteroid.y * 4 / ASTEROID_FADE_ROWS {
+                ..=0 => Gray::Dark,
+                1 => Gray::Mid,
+                2 => Gray::Light,
+                _ => Gray::White,
+            };
+            asteroid.shape.draw(asteroid.x.round(), asteroid.y, |x, y| {
+                if dither.is_lit(level, x as i32, y as i32) {
+                    frame.set_pixel(x, y, true);
+                }
+            });
         }
 
         // Draw bullets (5px vertical lines)
         for &(x, y) in &self.bullets {
             Line::new(Point::new(x, y), Point::new(x, y + 5))
                 .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
-                .draw(&mut self.display)
+                .draw(&mut self.frame)
                 .unwrap();
         }
 
-        // Draw ship with body and wings
-        let size = 4;
+        self.particles.draw(&mut self.frame).unwrap();
 
-        // Main body (narrow triangle - half width)
-        Triangle::new(
-            Point::new(self.triangle_x, self.triangle_y - size), // Top point
-            Point::new(self.triangle_x - size / 2, self.triangle_y + size), // Bottom left
-            Point::new(self.triangle_x + size / 2, self.triangle_y + size), // Bottom right
-        )
-        .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
-        .draw(&mut self.display)
-        .unwrap();
+        // The last two explosion frames fade out
+        for (x, y, animation) in &self.explosions {
+            let level = match sprite::EXPLOSION.frame_count() - animation.frame() {
+                ..=1 => Gray::Dark,
+                2 => Gray::Mid,
+                _ => Gray::White,
+            };
+            sprite::EXPLOSION
+                .draw(
+                    animation.frame(),
+                    Point::new(*x, *y),
+                    &mut dither.draw_gray(level, &mut self.frame),
+                )
+                .unwrap();
+        }
 
-        // Wings (wide triangle - half height, original width, pointing up)
-        Triangle::new(
-            Point::new(self.triangle_x, self.triangle_y), // Top point
-            Point::new(self.triangle_x - size, self.triangle_y + size), // Bottom left
-            Point::new(self.triangle_x + size, self.triangle_y + size), // Bottom right
-        )
-        .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
-        .draw(&mut self.display)
-        .unwrap();
+        // Draw ship centered on its position
+        let ship_x = self.triangle_x.round();
+        sprite::SHIP
+            .draw(0, Point::new(ship_x, self.triangle_y), &mut self.frame)
+            .unwrap();
+
+        if let Some((score, since)) = self.game_over {
+            if since.elapsed() < GAME_OVER_BANNER {
+                self.draw_game_over(score);
+            } else {
+                self.game_over = None;
+            }
+        }
+
+        // The overlay stays on top of a transition
+        self.effects.draw_transition(&mut self.frame).unwrap();
+
+        #[cfg(feature = "debug-overlay")]
+        {
+            if self.overlay.is_visible() {
+                self.draw_overlay();
+            }
+            self.frame_times.render = render_start.elapsed().as_micros();
+        }
 
-        self.display.flush().unwrap();
+        #[cfg(feature = "debug-overlay")]
+        let present_start = Instant::now();
+        self.presenter.present(&self.frame);
+        #[cfg(feature = "debug-overlay")]
+        {
+            self.frame_times.present = present_start.elapsed().as_micros();
+        }
+    }
+
+    /// Draws the debug overlay over the finished frame
+    #[cfg(feature = "debug-overlay")]
+    fn draw_overlay(&mut self) {
+        self.overlay.sample_stack();
+        let counts = EntityCounts {
+            bullets: Usage {
+                len: self.bullets.len(),
+                capacity: self.bullets.capacity(),
+            },
+            asteroids: Usage {
+                len: self.asteroids.len(),
+                capacity: self.asteroids.capacity(),
+            },
+            particles: Usage {
+                len: self.particles.len(),
+                capacity: self.particles.capacity(),
+            },
+        };
+        self.overlay.draw(&counts, &mut self.frame).unwrap();
     }
 
+    /// Counts a short tap of both buttons, two in a row toggle the debug overlay
+    #[cfg(feature = "debug-overlay")]
+    fn tap_both_buttons(&mut self) {
+        let double_tap = self
+            .last_both_tap
+            .is_some_and(|tap| tap.elapsed() < DOUBLE_TAP_GAP);
+        if double_tap {
+            self.overlay.toggle();
+            self.last_both_tap = None;
+            self.render();
+        } else {
+            self.last_both_tap = Some(Instant::now());
+        }
+    }
+
+    /// Taps of both buttons only toggle the debug overlay, which this build doesn't have
+    #[cfg(not(feature = "debug-overlay"))]
+    fn tap_both_buttons(&mut self) {}
+
     /// Main run loop - runs at the configured framerate
     /// Uses 4 fps when sleeping to save power
     pub fn run(&mut self) -> ! {
         loop {
+            let frame_period = self.last_frame_start.elapsed();
             let frame_start = Instant::now();
+            self.last_frame_start = frame_start;
 
-            let state = State {
-                button_left: self.button_left.is_low(),
-                button_right: self.button_right.is_low(),
-            };
+            let mut state = State::new();
+            self.buttons.poll(&mut state);
+            if let Some(joystick) = &mut self.joystick {
+                joystick.poll(&mut state);
+            }
+            if let Some(encoder) = &mut self.encoder {
+                encoder.poll(&mut state);
+            }
+            if let Some(console) = &mut self.console {
+                console.poll(&mut state);
+                if let Some(line) = console.take_line() {
+                    match command::parse(&line) {
+                        Ok(command) => self.execute(command),
+                        Err(command::ParseError::Empty) => {}
+                        Err(e) => println!("Error: {}", e.message()),
+                    }
+                }
+            }
 
             self.main_loop(&state);
 
+            let deep_sleep_due = self.is_sleeping
+                && self.deep_sleep_timeout.as_millis() > 0
+                && self.last_input_time.elapsed() > self.deep_sleep_timeout;
+            if deep_sleep_due {
+                self.deep_sleep();
+                continue;
+            }
+
+            if !self.is_sleeping {
+                // The dither follows the frame rate reached, which can fall short of the target
+                let period = frame_period.as_micros();
+                self.frame_period_avg = (self.frame_period_avg * 7 + period) / 8;
+                self.dither
+                    .advance((1_000_000 / self.frame_period_avg.max(1)) as u32);
+                self.effects.tick();
+                self.presenter.set_inverted(self.effects.is_inverted());
+
+                let frame_time = frame_start.elapsed().as_micros();
+                self.frame_time_total += frame_time;
+                self.timed_frames += 1;
+
+                #[cfg(feature = "debug-overlay")]
+                {
+                    let mut times = core::mem::take(&mut self.frame_times);
+                    times.update = frame_time.saturating_sub(times.render + times.present);
+                    times.transfer = self.presenter.last_transfer_micros() as u64;
+                    self.overlay.record_frame(frame_time, times);
+                }
+            }
+
             let target_duration = if self.is_sleeping {
                 self.sleep_frame_duration
             } else {
@@ -522,3 +2020,19 @@ impl App {
         }
     }
 }
+
+/// Adds the pins of a configured input to `in_use` if every one of them passes
+/// [`input::check_input_pin`]. Otherwise prints why and leaves the input out.
+fn claim_pins(in_use: &mut heapless::Vec<u8, 16>, pins: &[u8], device: &str) -> bool {
+    let claimed = in_use.len();
+    for &pin in pins {
+        if let Err(e) = input::check_input_pin(pin, in_use) {
+            println!("{} disabled, GPIO{}: {}", device, pin, e.message());
+            in_use.truncate(claimed);
+            return false;
+        }
+        // Sized for every pin the firmware and the board config can claim
+        in_use.push(pin).unwrap();
+    }
+    true
+}
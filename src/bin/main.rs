@@ -10,10 +10,12 @@ use esp_hal::main;
 use esp_println::println;
 
 use esp_asteroids::app::{App, AppConfig};
+use esp_asteroids::movement::Movement;
+use esp_asteroids::settings::Rotation;
 
 #[panic_handler]
-fn panic(_: &core::panic::PanicInfo) -> ! {
-    loop {}
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    esp_asteroids::crash::handle_panic(info)
 }
 
 // This creates a default app-descriptor required by the esp-idf bootloader.
@@ -26,7 +28,18 @@ fn main() -> ! {
 
     let mut app = App::setup(AppConfig {
         target_fps: 30,
-        sleep_timeout_secs: 10, // Sleep after 10 seconds (display off + 4 fps, 0 = disabled)
+        dim_timeout_secs: 30,         // Dim after 30 idle seconds (0 = never)
+        sleep_timeout_secs: 120,      // Display off + 4 fps after 2 minutes (0 = disabled)
+        deep_sleep_timeout_secs: 300, // Light sleep after 5 minutes (0 = disabled)
+        attract_timeout_secs: 10,     // Title screen and demo after 10 idle seconds
+        joystick: false,              // Set to true if an analog stick is wired to GPIO34
+        encoder: None,                // e.g. Some(EncoderPins { a: 25, b: 26, switch: 27 })
+        fire_button: None,            // e.g. Some(5) for a third button on GPIO5
+        serial_console: true,         // Play and send `:` commands over the USB serial port
+        // Movement::Inertia(InertiaTuning::default()) for smooth acceleration and coasting
+        movement: Movement::default(),
+        rotation: Rotation::Deg0, // Rotation::Deg90 for a portrait playfield
+        grayscale: false,         // Dithered gray stars, asteroids and explosions, 30+ fps
     });
 
     app.run()
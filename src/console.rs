@@ -0,0 +1,166 @@
+use esp_hal::Blocking;
+use esp_hal::uart::UartRx;
+use esp_println::print;
+
+use crate::input::InputSource;
+use crate::state::{AXIS_MAX, State};
+
+/// Frames a key stays pressed after its last byte arrives. Terminals only send key repeats,
+/// never releases, so this must bridge the gap between repeats (~30-50 ms).
+const KEY_HOLD_FRAMES: u8 = 6;
+
+/// Longest command line accepted, longer input is discarded
+const LINE_CAPACITY: usize = 32;
+
+/// Decoder state for the incoming byte stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Keys,
+    /// Got ESC, waiting for `[`
+    Escape,
+    /// Got `ESC [`, waiting for the final byte of an arrow key
+    Csi,
+    /// Typing a command line after `:`
+    Line,
+}
+
+/// Serial console on the UART receive line.
+///
+/// Outside of a command, `a`/`d` or the arrow keys steer and space fires.
+/// Typing `:` starts a command line that is submitted with Enter.
+pub struct Console {
+    rx: UartRx<'static, Blocking>,
+    mode: Mode,
+    left_frames: u8,
+    right_frames: u8,
+    fire_frames: u8,
+    line: heapless::String<LINE_CAPACITY>,
+    line_overflowed: bool,
+    /// A finished command line waiting to be taken
+    pending: Option<heapless::String<LINE_CAPACITY>>,
+}
+
+impl Console {
+    pub fn new(rx: UartRx<'static, Blocking>) -> Self {
+        Self {
+            rx,
+            mode: Mode::Keys,
+            left_frames: 0,
+            right_frames: 0,
+            fire_frames: 0,
+            line: heapless::String::new(),
+            line_overflowed: false,
+            pending: None,
+        }
+    }
+
+    /// Takes the last command line submitted, if any
+    pub fn take_line(&mut self) -> Option<heapless::String<LINE_CAPACITY>> {
+        self.pending.take()
+    }
+
+    /// Drain the receive FIFO without blocking
+    fn receive(&mut self) {
+        let mut buffer = [0u8; 16];
+        loop {
+            let count = match self.rx.read_buffered(&mut buffer) {
+                Ok(count) => count,
+                // Overruns and framing errors just drop the bytes, the next key press recovers
+                Err(_) => 0,
+            };
+            if count == 0 {
+                break;
+            }
+            for &byte in &buffer[..count] {
+                self.handle_byte(byte);
+            }
+        }
+    }
+
+    fn handle_byte(&mut self, byte: u8) {
+        self.mode = match self.mode {
+            Mode::Keys => match byte {
+                b'a' | b'A' => self.press_left(),
+                b'd' | b'D' => self.press_right(),
+                b' ' => {
+                    self.fire_frames = KEY_HOLD_FRAMES;
+                    Mode::Keys
+                }
+                0x1B => Mode::Escape,
+                b':' => {
+                    print!(":");
+                    self.line.clear();
+                    self.line_overflowed = false;
+                    Mode::Line
+                }
+                _ => Mode::Keys,
+            },
+            Mode::Escape if byte == b'[' => Mode::Csi,
+            Mode::Escape => Mode::Keys,
+            Mode::Csi => match byte {
+                b'D' => self.press_left(),
+                b'C' => self.press_right(),
+                _ => Mode::Keys,
+            },
+            Mode::Line => match byte {
+                b'\r' | b'\n' => {
+                    print!("\r\n");
+                    if self.line_overflowed {
+                        print!("command too long\r\n");
+                    } else {
+                        self.pending = Some(self.line.clone());
+                    }
+                    Mode::Keys
+                }
+                // Backspace and delete
+                0x08 | 0x7F => {
+                    if self.line.pop().is_some() {
+                        print!("\x08 \x08");
+                    }
+                    Mode::Line
+                }
+                byte if byte.is_ascii_graphic() || byte == b' ' => {
+                    if self.line.push(byte as char).is_err() {
+                        self.line_overflowed = true;
+                    }
+                    print!("{}", byte as char);
+                    Mode::Line
+                }
+                _ => Mode::Line,
+            },
+        };
+    }
+
+    fn press_left(&mut self) -> Mode {
+        self.left_frames = KEY_HOLD_FRAMES;
+        self.right_frames = 0;
+        Mode::Keys
+    }
+
+    fn press_right(&mut self) -> Mode {
+        self.right_frames = KEY_HOLD_FRAMES;
+        self.left_frames = 0;
+        Mode::Keys
+    }
+}
+
+impl InputSource for Console {
+    fn poll(&mut self, state: &mut State) {
+        self.receive();
+
+        if self.left_frames > 0 {
+            self.left_frames -= 1;
+            state.button_left = true;
+            state.push_axis(-AXIS_MAX);
+        }
+        if self.right_frames > 0 {
+            self.right_frames -= 1;
+            state.button_right = true;
+            state.push_axis(AXIS_MAX);
+        }
+        if self.fire_frames > 0 {
+            self.fire_frames -= 1;
+            state.button_fire = true;
+        }
+    }
+}
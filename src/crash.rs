@@ -0,0 +1,132 @@
+//! Panic handling: the panic is printed, shown on the display and kept in flash for the
+//! diagnostics screen, then the board restarts.
+
+use core::fmt::Write;
+use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use embedded_graphics::{
+    mono_font::{MonoTextStyle, ascii::FONT_4X6},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use esp_hal::delay::Delay;
+use esp_hal::peripherals::{CPU_CTRL, FLASH};
+use esp_hal::system::{Cpu, CpuControl, software_reset};
+use esp_println::println;
+use esp_storage::FlashStorage;
+
+use crate::display::{self, Panel};
+use crate::framebuffer::{FrameBuffer, HEIGHT, PanelMirror, WIDTH};
+use crate::storage;
+use crate::strings::Truncating;
+
+/// Longest crash description kept in flash, longer ones are cut off
+pub const CRASH_LOG_LEN: usize = 120;
+
+/// Location and message of the last panic
+pub type CrashLog = heapless::String<CRASH_LOG_LEN>;
+
+/// How long the crash screen stays up before restarting
+const RESTART_DELAY_MS: u32 = 5000;
+
+/// Height of one line of the crash screen in pixels
+const LINE_HEIGHT: i32 = 6;
+
+/// Set once a panic is being handled, so a panic inside the handler doesn't recurse
+static PANICKING: AtomicBool = AtomicBool::new(false);
+
+/// Reports a panic and restarts, call from the `#[panic_handler]`
+pub fn handle_panic(info: &PanicInfo) -> ! {
+    if PANICKING.swap(true, Ordering::Relaxed) {
+        println!("Panic while handling a panic, restarting");
+        software_reset();
+    }
+
+    // Stop the other core first, it may be halfway through using the display.
+    // Safety: the presenter's CpuControl is never used again once this runs
+    let mut cpu_control = CpuControl::new(unsafe { CPU_CTRL::steal() });
+    let other = match Cpu::current() {
+        Cpu::ProCpu => Cpu::AppCpu,
+        Cpu::AppCpu => Cpu::ProCpu,
+    };
+    // Safety: the core running this handler is never the one being parked
+    unsafe { cpu_control.park_core(other) };
+
+    println!("{}", info);
+
+    let mut log = CrashLog::new();
+    let mut writer = Truncating::new(&mut log);
+    if let Some(location) = info.location() {
+        let _ = write!(writer, "{}:{}: ", location.file(), location.line());
+    }
+    let _ = write!(writer, "{}", info.message());
+
+    // Saved before touching the display, which is the likelier of the two to fail again
+    // Safety: the game is over, nothing else uses the flash anymore
+    let mut flash = FlashStorage::new(unsafe { FLASH::steal() });
+    if let Err(e) = storage::save_crash_log(&log, &mut flash) {
+        println!("Failed to save crash log: {:?}", e);
+    }
+
+    show_crash_screen(&log);
+
+    Delay::new().delay_millis(RESTART_DELAY_MS);
+    software_reset()
+}
+
+/// Draws the crash description on a freshly set up panel
+fn show_crash_screen(log: &str) {
+    let mut frame = FrameBuffer::new();
+    let style = MonoTextStyle::new(&FONT_4X6, BinaryColor::On);
+    let columns = WIDTH / FONT_4X6.character_size.width as usize;
+    let rows = HEIGHT / LINE_HEIGHT as usize;
+
+    let lines = core::iter::once("Crashed, restarting...").chain(wrap(log, columns));
+    for (row, line) in lines.take(rows).enumerate() {
+        let _ = Text::with_baseline(
+            line,
+            Point::new(0, row as i32 * LINE_HEIGHT),
+            style,
+            Baseline::Top,
+        )
+        .draw(&mut frame);
+    }
+
+    // Safety: the display task's core is parked, so the panel is ours
+    let mut display = match unsafe { display::reinit() } {
+        Ok(display) => display,
+        Err(e) => {
+            println!("Failed to set up the display for the crash screen: {:?}", e);
+            return;
+        }
+    };
+    let mirror = PanelMirror::new();
+    for span in mirror.dirty_spans(&frame) {
+        if display
+            .write_span(span.page, span.start, frame.span_bytes(&span))
+            .is_err()
+        {
+            println!("Failed to show the crash screen");
+            return;
+        }
+    }
+}
+
+/// Splits `text` into lines of at most `columns` characters
+pub fn wrap(text: &str, columns: usize) -> impl Iterator<Item = &str> {
+    let mut rest = text;
+    core::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest
+            .char_indices()
+            .nth(columns)
+            .map_or(rest.len(), |(i, _)| i);
+        let (line, tail) = rest.split_at(end);
+        rest = tail;
+        Some(line)
+    })
+}
@@ -0,0 +1,281 @@
+//! Display panels the game can drive, selected with cargo features:
+//!
+//! - default: SSD1306 128x64 on I2C (SDA GPIO21, SCL GPIO22)
+//! - `sh1106`: SH1106 128x64 on I2C (SDA GPIO21, SCL GPIO22)
+//! - `ssd1306-128x32`: SSD1306 128x32 on I2C (SDA GPIO21, SCL GPIO22)
+//! - `spi-display`: SSD1306 or SSD1309 128x64 on SPI (SCK GPIO14, MOSI GPIO13, CS GPIO15,
+//!   DC GPIO4, RST GPIO16)
+//!
+//! The playfield size follows the panel through [`crate::framebuffer::WIDTH`] and
+//! [`crate::framebuffer::HEIGHT`]. Panels are always driven unrotated, the frame buffer
+//! takes care of the rotation setting.
+
+use display_interface::{DataFormat::U8, DisplayError, WriteOnlyDataCommand};
+use ssd1306::{Ssd1306, mode::BasicMode, prelude::*};
+
+#[cfg(feature = "spi-display")]
+use esp_hal::{
+    delay::Delay,
+    gpio::{Level, Output, OutputConfig},
+    peripherals::{GPIO4, GPIO13, GPIO14, GPIO15, GPIO16, SPI2},
+    spi::master::{Config as SpiConfig, Spi},
+    time::Rate,
+};
+#[cfg(not(feature = "spi-display"))]
+use esp_hal::{
+    i2c::master::{Config as I2cConfig, I2c},
+    peripherals::{GPIO21, GPIO22, I2C0},
+    time::Rate,
+};
+
+#[cfg(any(
+    all(feature = "sh1106", feature = "ssd1306-128x32"),
+    all(feature = "sh1106", feature = "spi-display"),
+    all(feature = "ssd1306-128x32", feature = "spi-display"),
+))]
+compile_error!(
+    "Only one of the `sh1106`, `ssd1306-128x32` and `spi-display` features can be enabled"
+);
+
+/// Contrast the panels are set up with, the SSD1306 driver's default
+pub const DEFAULT_CONTRAST: u8 = 0x5F;
+
+/// Why a panel couldn't be set up
+#[derive(Debug)]
+pub enum InitError {
+    /// The bus driver rejected its configuration
+    Bus,
+    /// The panel didn't take its init commands
+    Panel(DisplayError),
+}
+
+/// A panel that accepts page memory spans, as produced by the frame buffer
+pub trait Panel {
+    /// Writes `data` into `page` starting at `column`
+    fn write_span(&mut self, page: u8, column: u8, data: &[u8]) -> Result<(), DisplayError>;
+
+    fn set_display_on(&mut self, on: bool) -> Result<(), DisplayError>;
+
+    /// Sets the segment drive current, 0 is dimmest and 255 brightest
+    fn set_contrast(&mut self, contrast: u8) -> Result<(), DisplayError>;
+
+    /// Shows lit pixels dark and dark pixels lit, without touching the display RAM
+    fn set_inverted(&mut self, inverted: bool) -> Result<(), DisplayError>;
+}
+
+impl<DI, SIZE> Panel for Ssd1306<DI, SIZE, BasicMode>
+where
+    DI: WriteOnlyDataCommand,
+    SIZE: DisplaySize,
+{
+    fn write_span(&mut self, page: u8, column: u8, data: &[u8]) -> Result<(), DisplayError> {
+        let top = page * 8;
+        self.set_draw_area((column, top), (column + data.len() as u8, top + 8))?;
+        self.draw(data)
+    }
+
+    fn set_display_on(&mut self, on: bool) -> Result<(), DisplayError> {
+        Ssd1306::set_display_on(self, on)
+    }
+
+    fn set_contrast(&mut self, contrast: u8) -> Result<(), DisplayError> {
+        self.set_brightness(Brightness::custom(2, contrast))
+    }
+
+    fn set_inverted(&mut self, inverted: bool) -> Result<(), DisplayError> {
+        self.set_invert(inverted)
+    }
+}
+
+/// Minimal SH1106 driver, the SSD1306 lookalike with 132 columns of RAM and page
+/// addressing only
+pub struct Sh1106<DI> {
+    interface: DI,
+}
+
+impl<DI: WriteOnlyDataCommand> Sh1106<DI> {
+    /// The 128 visible columns are centered in the 132 column RAM
+    const COLUMN_OFFSET: u8 = 2;
+
+    /// Sent by [`Self::init`], each command with its arguments on one line
+    #[rustfmt::skip]
+    const INIT_COMMANDS: &[u8] = &[
+        0xAE, // Display off
+        0xD5, 0x80, // Clock divide ratio and oscillator frequency
+        0xA8, 0x3F, // Multiplex ratio, 64 rows
+        0xD3, 0x00, // Display offset
+        0x40, // Start line 0
+        0xAD, 0x8B, // Internal DC-DC converter on
+        0xA1, // Segment remap, column 0 on the left
+        0xC8, // COM scan from the bottom up
+        0xDA, 0x12, // Alternative COM pin configuration
+        0x81, DEFAULT_CONTRAST, // Contrast
+        0xD9, 0x22, // Pre-charge period
+        0xDB, 0x35, // VCOMH deselect level
+        0xA4, // Show RAM contents
+        0xA6, // Not inverted
+        0xAF, // Display on
+    ];
+
+    pub fn new(interface: DI) -> Self {
+        Self { interface }
+    }
+
+    /// Configures a 128x64 panel and turns it on
+    pub fn init(&mut self) -> Result<(), DisplayError> {
+        self.interface.send_commands(U8(Self::INIT_COMMANDS))
+    }
+}
+
+impl<DI: WriteOnlyDataCommand> Panel for Sh1106<DI> {
+    fn write_span(&mut self, page: u8, column: u8, data: &[u8]) -> Result<(), DisplayError> {
+        let column = column + Self::COLUMN_OFFSET;
+        self.interface
+            .send_commands(U8(&[0xB0 | page, column & 0x0F, 0x10 | (column >> 4)]))?;
+        self.interface.send_data(U8(data))
+    }
+
+    fn set_display_on(&mut self, on: bool) -> Result<(), DisplayError> {
+        self.interface
+            .send_commands(U8(&[if on { 0xAF } else { 0xAE }]))
+    }
+
+    fn set_contrast(&mut self, contrast: u8) -> Result<(), DisplayError> {
+        self.interface.send_commands(U8(&[0x81, contrast]))
+    }
+
+    fn set_inverted(&mut self, inverted: bool) -> Result<(), DisplayError> {
+        self.interface
+            .send_commands(U8(&[if inverted { 0xA7 } else { 0xA6 }]))
+    }
+}
+
+#[cfg(feature = "sh1106")]
+pub type Display = Sh1106<I2CInterface<I2c<'static, esp_hal::Blocking>>>;
+
+#[cfg(feature = "ssd1306-128x32")]
+pub type Display =
+    Ssd1306<I2CInterface<I2c<'static, esp_hal::Blocking>>, DisplaySize128x32, BasicMode>;
+
+#[cfg(feature = "spi-display")]
+pub type Display = Ssd1306<
+    SPIInterface<
+        embedded_hal_bus::spi::ExclusiveDevice<
+            Spi<'static, esp_hal::Blocking>,
+            Output<'static>,
+            embedded_hal_bus::spi::NoDelay,
+        >,
+        Output<'static>,
+    >,
+    DisplaySize128x64,
+    BasicMode,
+>;
+
+#[cfg(not(any(
+    feature = "sh1106",
+    feature = "ssd1306-128x32",
+    feature = "spi-display"
+)))]
+pub type Display =
+    Ssd1306<I2CInterface<I2c<'static, esp_hal::Blocking>>, DisplaySize128x64, BasicMode>;
+
+/// Sets up the I2C panel - SDA on GPIO21, SCL on GPIO22
+#[cfg(not(feature = "spi-display"))]
+pub fn init(
+    i2c: I2C0<'static>,
+    sda: GPIO21<'static>,
+    scl: GPIO22<'static>,
+) -> Result<Display, InitError> {
+    let i2c = I2c::new(
+        i2c,
+        I2cConfig::default().with_frequency(Rate::from_hz(400_000)),
+    )
+    .map_err(|_| InitError::Bus)?
+    .with_sda(sda)
+    .with_scl(scl);
+
+    let interface = I2CInterface::new(i2c, 0x3C, 0x40);
+
+    #[cfg(feature = "sh1106")]
+    let mut display = Sh1106::new(interface);
+    #[cfg(feature = "ssd1306-128x32")]
+    let mut display = Ssd1306::new(interface, DisplaySize128x32, DisplayRotation::Rotate0);
+    #[cfg(not(any(feature = "sh1106", feature = "ssd1306-128x32")))]
+    let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0);
+
+    display.init().map_err(InitError::Panel)?;
+    Ok(display)
+}
+
+/// Pins of the SPI panel
+#[cfg(feature = "spi-display")]
+pub struct SpiPins {
+    pub sck: GPIO14<'static>,
+    pub mosi: GPIO13<'static>,
+    pub cs: GPIO15<'static>,
+    pub dc: GPIO4<'static>,
+    pub rst: GPIO16<'static>,
+}
+
+/// Sets up the SPI panel, SSD1309 modules take the same commands as the SSD1306
+#[cfg(feature = "spi-display")]
+pub fn init(spi: SPI2<'static>, pins: SpiPins) -> Result<Display, InitError> {
+    let spi = Spi::new(spi, SpiConfig::default().with_frequency(Rate::from_mhz(8)))
+        .map_err(|_| InitError::Bus)?
+        .with_sck(pins.sck)
+        .with_mosi(pins.mosi);
+    let cs = Output::new(pins.cs, Level::High, OutputConfig::default());
+    let dc = Output::new(pins.dc, Level::Low, OutputConfig::default());
+    let mut rst = Output::new(pins.rst, Level::High, OutputConfig::default());
+
+    // Pulse reset, the controller needs a few microseconds low before taking commands.
+    // Modules pull RST high themselves, so the pin can be released afterwards.
+    let delay = Delay::new();
+    rst.set_low();
+    delay.delay_millis(1);
+    rst.set_high();
+    delay.delay_millis(1);
+
+    let device = embedded_hal_bus::spi::ExclusiveDevice::new_no_delay(spi, cs)
+        .map_err(|_| InitError::Bus)?;
+    let interface = SPIInterface::new(device, dc);
+    let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0);
+
+    display.init().map_err(InitError::Panel)?;
+    Ok(display)
+}
+
+/// Sets the panel up again from scratch, for the panic handler once the display task can't
+/// be trusted with it anymore. The bus is reconfigured, which also recovers it from a
+/// transfer that was cut off halfway. Fails instead of panicking, the panic handler
+/// can't afford another panic.
+///
+/// # Safety
+///
+/// Nothing else may use the display peripheral and pins afterwards, e.g. the core running
+/// the display task must be parked.
+pub unsafe fn reinit() -> Result<Display, InitError> {
+    use esp_hal::peripherals;
+
+    #[cfg(not(feature = "spi-display"))]
+    unsafe {
+        init(
+            peripherals::I2C0::steal(),
+            peripherals::GPIO21::steal(),
+            peripherals::GPIO22::steal(),
+        )
+    }
+    #[cfg(feature = "spi-display")]
+    unsafe {
+        init(
+            peripherals::SPI2::steal(),
+            SpiPins {
+                sck: peripherals::GPIO14::steal(),
+                mosi: peripherals::GPIO13::steal(),
+                cs: peripherals::GPIO15::steal(),
+                dc: peripherals::GPIO4::steal(),
+                rst: peripherals::GPIO16::steal(),
+            },
+        )
+    }
+}
@@ -0,0 +1,115 @@
+use esp_hal::gpio::{AnyPin, Input, InputConfig, Pull};
+use esp_hal::pcnt::{Pcnt, channel, unit::Unit};
+use esp_hal::peripherals::PCNT;
+
+use crate::input::InputSource;
+use crate::state::State;
+
+/// Quadrature counts produced by one mechanical detent (both edges of both channels)
+const COUNTS_PER_DETENT: i16 = 4;
+
+/// Glitch filter in APB clock cycles (~12.8us at 80 MHz) to debounce the contacts
+const FILTER_CYCLES: u16 = 1023;
+
+/// GPIO numbers of a rotary encoder with push switch, all wired active low
+#[derive(Debug, Clone, Copy)]
+pub struct EncoderPins {
+    pub a: u8,
+    pub b: u8,
+    pub switch: u8,
+}
+
+/// Quadrature rotary encoder counted in hardware by PCNT unit 0
+pub struct Encoder {
+    unit: Unit<'static, 0>,
+    // The phase inputs stay owned so their pull-ups remain configured
+    _a: Input<'static>,
+    _b: Input<'static>,
+    switch: Input<'static>,
+    last_count: i16,
+    /// Counts that haven't added up to a whole detent yet
+    remainder: i16,
+}
+
+impl Encoder {
+    /// Configure the encoder on the given pins.
+    ///
+    /// # Safety
+    ///
+    /// The pins are claimed by number. Each must pass [`check_input_pin`] against every
+    /// other pin in use, including the other two encoder pins, so nothing else drives them.
+    ///
+    /// [`check_input_pin`]: crate::input::check_input_pin
+    pub unsafe fn new(pcnt: PCNT<'static>, pins: EncoderPins) -> Self {
+        let config = InputConfig::default().with_pull(Pull::Up);
+        // Safety: the caller checked that these pins are dedicated to the encoder
+        let (a, b, switch) = unsafe {
+            (
+                Input::new(AnyPin::steal(pins.a), config),
+                Input::new(AnyPin::steal(pins.b), config),
+                Input::new(AnyPin::steal(pins.switch), config),
+            )
+        };
+
+        let pcnt = Pcnt::new(pcnt);
+        let unit = pcnt.unit0;
+        // No limits: the counter wraps at the i16 range, which delta tracking handles
+        unit.set_low_limit(None).unwrap();
+        unit.set_high_limit(None).unwrap();
+        unit.set_filter(Some(FILTER_CYCLES)).unwrap();
+        unit.clear();
+
+        // Decode both edges of both phases, each channel using the other phase as direction
+        let ch0 = &unit.channel0;
+        ch0.set_ctrl_signal(a.peripheral_input());
+        ch0.set_edge_signal(b.peripheral_input());
+        ch0.set_ctrl_mode(channel::CtrlMode::Reverse, channel::CtrlMode::Keep);
+        ch0.set_input_mode(channel::EdgeMode::Increment, channel::EdgeMode::Decrement);
+
+        let ch1 = &unit.channel1;
+        ch1.set_ctrl_signal(b.peripheral_input());
+        ch1.set_edge_signal(a.peripheral_input());
+        ch1.set_ctrl_mode(channel::CtrlMode::Reverse, channel::CtrlMode::Keep);
+        ch1.set_input_mode(channel::EdgeMode::Decrement, channel::EdgeMode::Increment);
+
+        unit.resume();
+
+        Self {
+            unit,
+            _a: a,
+            _b: b,
+            switch,
+            last_count: 0,
+            remainder: 0,
+        }
+    }
+
+    /// Whole detents turned since the last call, positive = clockwise
+    fn take_detents(&mut self) -> i16 {
+        let count = self.unit.value();
+        self.remainder += count.wrapping_sub(self.last_count);
+        self.last_count = count;
+
+        let detents = self.remainder / COUNTS_PER_DETENT;
+        self.remainder -= detents * COUNTS_PER_DETENT;
+        detents
+    }
+}
+
+impl InputSource for Encoder {
+    fn poll(&mut self, state: &mut State) {
+        let detents = self.take_detents();
+        state.push_detents(detents);
+
+        // A click in either direction also acts as a button press for menu navigation
+        if detents < 0 {
+            state.button_left = true;
+        }
+        if detents > 0 {
+            state.button_right = true;
+        }
+        if self.switch.is_low() {
+            state.button_fire = true;
+        }
+    }
+}
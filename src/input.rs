@@ -0,0 +1,92 @@
+use esp_hal::gpio::{Input, WakeEvent};
+
+use crate::state::{AXIS_MAX, State};
+
+/// A physical control that contributes to the per-frame input [`State`].
+///
+/// Every wired source is polled once per frame into the same `State`, so the game
+/// logic never needs to know which controls the board actually has.
+pub trait InputSource {
+    /// Merge the current readings of this source into `state`
+    fn poll(&mut self, state: &mut State);
+}
+
+/// The left/right push buttons and optional fire button - pull-up resistors, active low (pressed = LOW)
+pub struct Buttons {
+    left: Input<'static>,
+    right: Input<'static>,
+    fire: Option<Input<'static>>,
+}
+
+impl Buttons {
+    pub fn new(left: Input<'static>, right: Input<'static>, fire: Option<Input<'static>>) -> Self {
+        Self { left, right, fire }
+    }
+
+    /// Returns true if a dedicated fire button is wired
+    pub fn has_fire(&self) -> bool {
+        self.fire.is_some()
+    }
+
+    /// Lets a pressed button wake the chip from light sleep
+    pub fn enable_wakeup(&mut self) {
+        let buttons = [&mut self.left, &mut self.right]
+            .into_iter()
+            .chain(self.fire.as_mut());
+        for button in buttons {
+            // Only edge triggers are unsupported, levels always work
+            button.wakeup_enable(true, WakeEvent::LowLevel).unwrap();
+        }
+    }
+}
+
+impl InputSource for Buttons {
+    fn poll(&mut self, state: &mut State) {
+        if self.left.is_low() {
+            state.button_left = true;
+            state.push_axis(-AXIS_MAX);
+        }
+        if self.right.is_low() {
+            state.button_right = true;
+            state.push_axis(AXIS_MAX);
+        }
+        if self.fire.as_ref().is_some_and(|fire| fire.is_low()) {
+            state.button_fire = true;
+        }
+    }
+}
+
+/// Why a GPIO from the board config can't be used for an active low input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinError {
+    /// Not a GPIO of the ESP32, or one wired to the SPI flash
+    Unavailable,
+    /// GPIO34-39 are input only and have no internal pull-up to hold them high
+    NoPullUp,
+    /// Already used by the display, the buttons, the serial port or another input
+    InUse,
+}
+
+impl PinError {
+    pub fn message(&self) -> &'static str {
+        match self {
+            PinError::Unavailable => "no such GPIO, or reserved for flash",
+            PinError::NoPullUp => "input only pin without a pull-up",
+            PinError::InUse => "already in use",
+        }
+    }
+}
+
+/// Checks that `pin` exists, can be pulled up and isn't one of `in_use`.
+///
+/// Inputs configured by number are claimed with `AnyPin::steal`, which is only sound once
+/// this has passed with every pin the firmware drives itself in `in_use`.
+pub fn check_input_pin(pin: u8, in_use: &[u8]) -> Result<(), PinError> {
+    match pin {
+        // Gaps in the ESP32 GPIO numbering, and the pins of the SPI flash
+        6..=11 | 20 | 24 | 28..=31 | 40.. => Err(PinError::Unavailable),
+        34..=39 => Err(PinError::NoPullUp),
+        _ if in_use.contains(&pin) => Err(PinError::InUse),
+        _ => Ok(()),
+    }
+}
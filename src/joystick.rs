@@ -0,0 +1,127 @@
+use esp_hal::Blocking;
+use esp_hal::analog::adc::{Adc, AdcConfig, AdcPin, Attenuation};
+use esp_hal::peripherals::{ADC1, GPIO34};
+
+use crate::input::InputSource;
+use crate::state::{AXIS_MAX, State};
+
+/// Highest raw reading of the 12-bit ADC
+const ADC_MAX: u16 = 4095;
+
+/// Deadzone used when no calibration has been stored yet
+const DEFAULT_DEADZONE: u16 = 200;
+
+/// Deflection (out of 127) past which the stick also counts as a left/right button press
+const BUTTON_THRESHOLD: i8 = 64;
+
+/// Number of samples averaged when calibrating the center position
+const CALIBRATION_SAMPLES: u32 = 32;
+
+/// Added to the noise measured while calibrating, so the resting stick never registers
+const DEADZONE_MARGIN: u16 = 60;
+
+/// Smallest calibrated deadzone, a quiet sampling window can underestimate the noise
+const MIN_DEADZONE: u16 = 100;
+
+/// Center and deadzone of the stick's horizontal axis, in raw ADC units
+#[derive(Debug, Clone, Copy)]
+pub struct Calibration {
+    pub center: u16,
+    pub deadzone: u16,
+}
+
+impl Default for Calibration {
+    fn default() -> Self {
+        Self {
+            center: ADC_MAX / 2,
+            deadzone: DEFAULT_DEADZONE,
+        }
+    }
+}
+
+impl Calibration {
+    /// Map a raw ADC reading to an axis value from -127 to 127
+    pub fn map(&self, raw: u16) -> i8 {
+        let offset = raw as i32 - self.center as i32;
+        let deadzone = self.deadzone as i32;
+        if offset.abs() <= deadzone {
+            return 0;
+        }
+
+        // Each side is scaled separately since the center is rarely exactly mid-range
+        let range = if offset < 0 {
+            self.center as i32
+        } else {
+            (ADC_MAX - self.center) as i32
+        };
+        let travel = (range - deadzone).max(1);
+        let scaled = (offset.abs() - deadzone) * AXIS_MAX as i32 / travel;
+
+        (scaled.min(AXIS_MAX as i32) * offset.signum()) as i8
+    }
+}
+
+/// Analog thumbstick with its horizontal axis wired to GPIO34 (ADC1)
+pub struct Joystick {
+    adc: Adc<'static, ADC1<'static>, Blocking>,
+    pin: AdcPin<GPIO34<'static>, ADC1<'static>>,
+    calibration: Calibration,
+}
+
+impl Joystick {
+    pub fn new(adc: ADC1<'static>, pin: GPIO34<'static>, calibration: Calibration) -> Self {
+        let mut config = AdcConfig::new();
+        // 11dB attenuation covers the full 0-3.3V swing of a potentiometer stick
+        let pin = config.enable_pin(pin, Attenuation::_11dB);
+        let adc = Adc::new(adc, config);
+
+        Self {
+            adc,
+            pin,
+            calibration,
+        }
+    }
+
+    /// Sample the resting stick position and use it as the new center, with a deadzone
+    /// covering the noise seen around it. The stick must not be touched while this runs.
+    pub fn calibrate(&mut self) -> Calibration {
+        let mut total = 0u32;
+        let (mut min, mut max) = (u16::MAX, 0);
+        for _ in 0..CALIBRATION_SAMPLES {
+            let raw = self.read_raw();
+            total += raw as u32;
+            min = min.min(raw);
+            max = max.max(raw);
+        }
+        let center = (total / CALIBRATION_SAMPLES) as u16;
+        let noise = (center - min).max(max - center);
+        self.calibration = Calibration {
+            center,
+            deadzone: (noise + DEADZONE_MARGIN).max(MIN_DEADZONE),
+        };
+        self.calibration
+    }
+
+    fn read_raw(&mut self) -> u16 {
+        loop {
+            if let Ok(value) = self.adc.read_oneshot(&mut self.pin) {
+                return value;
+            }
+        }
+    }
+}
+
+impl InputSource for Joystick {
+    fn poll(&mut self, state: &mut State) {
+        let raw = self.read_raw();
+        let axis = self.calibration.map(raw);
+
+        state.push_axis(axis);
+        if axis <= -BUTTON_THRESHOLD {
+            state.button_left = true;
+        }
+        if axis >= BUTTON_THRESHOLD {
+            state.button_right = true;
+        }
+    }
+}
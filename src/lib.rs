@@ -1,5 +1,19 @@
 #![no_std]
 
+// The hardware independent part lives in its own crate so it can be tested on the host
+pub use asteroids_game::{
+    asteroid, attract, collision, command, effects, fixed, fonts, framebuffer, gray, leaderboard,
+    movement, particles, rng, settings, sprite, starfield, state, strings, ui,
+};
+
 pub mod app;
-pub mod state;
+pub mod console;
+pub mod crash;
+pub mod display;
+pub mod encoder;
+pub mod input;
+pub mod joystick;
+#[cfg(feature = "debug-overlay")]
+pub mod overlay;
+pub mod presenter;
 pub mod storage;
@@ -0,0 +1,167 @@
+//! On-screen performance overlay.
+//!
+//! Only compiled with the `debug-overlay` feature, it's then toggled at runtime by tapping
+//! both buttons twice or with the `debug` console command.
+
+use core::fmt::Write;
+
+use embedded_graphics::{
+    mono_font::{MonoTextStyleBuilder, ascii::FONT_4X6},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use esp_hal::time::{Duration, Instant};
+
+/// Height of one overlay line in pixels
+const LINE_HEIGHT: i32 = 6;
+
+unsafe extern "C" {
+    /// Lowest address of the PRO core stack, from the esp-hal linker script
+    static _stack_end_cpu0: u32;
+}
+
+/// Fill level of a fixed-capacity collection
+#[derive(Debug, Clone, Copy)]
+pub struct Usage {
+    pub len: usize,
+    pub capacity: usize,
+}
+
+/// Counts shown on the overlay that only the caller knows
+#[derive(Debug, Clone, Copy)]
+pub struct EntityCounts {
+    pub bullets: Usage,
+    pub asteroids: Usage,
+    pub particles: Usage,
+}
+
+/// Frame timings measured by the game loop, in microseconds
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameTimes {
+    /// Simulation, everything in the frame that isn't drawing or presenting
+    pub update: u64,
+    pub render: u64,
+    /// Handing the frame to the display task
+    pub present: u64,
+    /// Sending the last frame to the display on the APP core
+    pub transfer: u64,
+}
+
+/// Measured frame rate and timings, drawn in the bottom left corner when visible
+pub struct DebugOverlay {
+    visible: bool,
+    window_start: Instant,
+    window_frames: u32,
+    window_worst: u64,
+    /// Frames counted in the last full second
+    fps: u32,
+    /// Longest frame of the last full second in microseconds
+    worst_frame: u64,
+    times: FrameTimes,
+    /// Smallest stack headroom seen so far in bytes
+    stack_free: usize,
+}
+
+impl Default for DebugOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            window_start: Instant::now(),
+            window_frames: 0,
+            window_worst: 0,
+            fps: 0,
+            worst_frame: 0,
+            times: FrameTimes::default(),
+            stack_free: usize::MAX,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Shows or hides the overlay
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Records a finished frame, `frame` is the whole frame's work in microseconds
+    pub fn record_frame(&mut self, frame: u64, times: FrameTimes) {
+        self.times = times;
+        self.window_frames += 1;
+        self.window_worst = self.window_worst.max(frame);
+
+        if self.window_start.elapsed() >= Duration::from_secs(1) {
+            self.fps = self.window_frames;
+            self.worst_frame = self.window_worst;
+            self.window_frames = 0;
+            self.window_worst = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    /// Updates the stack headroom estimate from the current stack depth
+    pub fn sample_stack(&mut self) {
+        let marker = 0u8;
+        let sp = &marker as *const u8 as usize;
+        // Safety: only the address of the linker symbol is used
+        let end = unsafe { &raw const _stack_end_cpu0 } as usize;
+        self.stack_free = self.stack_free.min(sp.saturating_sub(end));
+    }
+
+    pub fn draw<D>(&self, counts: &EntityCounts, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let mut lines: [heapless::String<32>; 4] = Default::default();
+        // Lines are far shorter than their capacity, so formatting can't fail
+        let _ = write!(
+            lines[0],
+            "{}fps max {}.{}ms",
+            self.fps,
+            self.worst_frame / 1000,
+            self.worst_frame % 1000 / 100
+        );
+        let _ = write!(
+            lines[1],
+            "u{} r{} p{} i{}us",
+            self.times.update, self.times.render, self.times.present, self.times.transfer
+        );
+        let _ = write!(
+            lines[2],
+            "B{}/{} A{}/{} P{}/{}",
+            counts.bullets.len,
+            counts.bullets.capacity,
+            counts.asteroids.len,
+            counts.asteroids.capacity,
+            counts.particles.len,
+            counts.particles.capacity
+        );
+        let _ = write!(lines[3], "stack free {}", self.stack_free);
+
+        // Black background keeps the text readable over the playfield
+        let style = MonoTextStyleBuilder::new()
+            .font(&FONT_4X6)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let top = target.bounding_box().size.height as i32 - lines.len() as i32 * LINE_HEIGHT;
+        for (i, line) in lines.iter().enumerate() {
+            Text::with_baseline(
+                line,
+                Point::new(0, top + i as i32 * LINE_HEIGHT),
+                style,
+                Baseline::Top,
+            )
+            .draw(target)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use core::cell::RefCell;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, Ordering};
+
+use critical_section::Mutex;
+use esp_hal::delay::Delay;
+use esp_hal::peripherals::CPU_CTRL;
+use esp_hal::system::{AppCoreGuard, Cpu, CpuControl, Stack};
+use esp_hal::time::Instant;
+use esp_println::println;
+
+use crate::display::{DEFAULT_CONTRAST, Display, Panel};
+use crate::framebuffer::{FrameBuffer, PanelMirror};
+
+/// Stack of the APP core, which does nothing but send frames to the display
+static mut APP_CORE_STACK: Stack<8192> = Stack::new();
+
+/// Latest finished frame that the display task hasn't picked up yet
+static PENDING_FRAME: Mutex<RefCell<Option<FrameBuffer>>> = Mutex::new(RefCell::new(None));
+
+static DISPLAY_ON: AtomicBool = AtomicBool::new(true);
+static CONTRAST: AtomicU8 = AtomicU8::new(DEFAULT_CONTRAST);
+static INVERTED: AtomicBool = AtomicBool::new(false);
+
+/// Set while the PRO core wants the display task to stop between frames
+static HOLD_REQUESTED: AtomicBool = AtomicBool::new(false);
+/// Set by the display task once it has stopped, outside any transfer or critical section
+static HELD: AtomicBool = AtomicBool::new(false);
+
+/// Transfer statistics since the last [`Presenter::take_stats`]
+static FRAMES_SENT: AtomicU32 = AtomicU32::new(0);
+static FRAMES_SKIPPED: AtomicU32 = AtomicU32::new(0);
+static BYTES_SENT: AtomicU32 = AtomicU32::new(0);
+
+/// How long sending the last frame took, in microseconds
+static LAST_TRANSFER_MICROS: AtomicU32 = AtomicU32::new(0);
+
+/// How long the display task waits before checking for a new frame again
+const IDLE_POLL_MICROS: u32 = 200;
+
+/// Display transfer counters, see [`Presenter::take_stats`]
+#[derive(Debug, Clone, Copy)]
+pub struct PresentStats {
+    /// Frames sent to the display
+    pub frames: u32,
+    /// Frames replaced by a newer one before the display task got to them
+    pub skipped: u32,
+    /// Bytes of pixel data sent
+    pub bytes: u32,
+}
+
+/// Sends finished frames to the display from the APP core.
+///
+/// The I2C transfer of one frame runs while the PRO core simulates and draws the next,
+/// so a slow display no longer caps the frame rate. When frames are produced faster than
+/// the display takes them, only the newest one is sent.
+pub struct Presenter {
+    cpu_control: CpuControl<'static>,
+    /// Keeps the display task running, dropping it parks the APP core
+    _app_core: AppCoreGuard<'static>,
+}
+
+impl Presenter {
+    /// Starts the display task on the APP core, which takes ownership of the display
+    pub fn start(cpu_ctrl: CPU_CTRL<'static>, display: Display) -> Self {
+        let mut cpu_control = CpuControl::new(cpu_ctrl);
+        // Safety: the stack is only ever handed to the APP core, and only once
+        let stack = unsafe { &mut *(&raw mut APP_CORE_STACK) };
+        let app_core = cpu_control
+            .start_app_core(stack, move || display_task(display))
+            .unwrap();
+        println!("Display task running on the APP core");
+
+        Self {
+            cpu_control,
+            _app_core: app_core,
+        }
+    }
+
+    /// Queues a finished frame, replacing one that wasn't sent yet
+    pub fn present(&mut self, frame: &FrameBuffer) {
+        critical_section::with(|cs| {
+            let replaced = PENDING_FRAME.borrow_ref_mut(cs).replace(frame.clone());
+            if replaced.is_some() {
+                FRAMES_SKIPPED.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Turns the panel on or off, the display task applies it before the next frame
+    pub fn set_display_on(&mut self, on: bool) {
+        DISPLAY_ON.store(on, Ordering::Relaxed);
+    }
+
+    /// Changes the panel contrast, the display task applies it before the next frame
+    pub fn set_contrast(&mut self, contrast: u8) {
+        CONTRAST.store(contrast, Ordering::Relaxed);
+    }
+
+    /// Inverts the whole panel, the display task applies it before the next frame
+    pub fn set_inverted(&mut self, inverted: bool) {
+        INVERTED.store(inverted, Ordering::Relaxed);
+    }
+
+    /// Stops the display task between frames and parks the APP core, e.g. before light
+    /// sleep. Waits for the task to finish the frame it's sending, so no I2C transfer or
+    /// critical section is left half done. Call [`Self::resume`] to restart it.
+    pub fn suspend(&mut self) {
+        HOLD_REQUESTED.store(true, Ordering::Release);
+        while !HELD.load(Ordering::Acquire) {}
+        // Safety: the task only spins on HOLD_REQUESTED now, holding no lock or bus
+        unsafe { self.cpu_control.park_core(Cpu::AppCpu) };
+    }
+
+    /// Restarts the display task after [`Self::suspend`]
+    pub fn resume(&mut self) {
+        self.cpu_control.unpark_core(Cpu::AppCpu);
+        HOLD_REQUESTED.store(false, Ordering::Release);
+    }
+
+    /// Time the display task spent sending the most recent frame, in microseconds
+    pub fn last_transfer_micros(&self) -> u32 {
+        LAST_TRANSFER_MICROS.load(Ordering::Relaxed)
+    }
+
+    /// Returns the transfer counters and resets them
+    pub fn take_stats(&mut self) -> PresentStats {
+        PresentStats {
+            frames: FRAMES_SENT.swap(0, Ordering::Relaxed),
+            skipped: FRAMES_SKIPPED.swap(0, Ordering::Relaxed),
+            bytes: BYTES_SENT.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Runs forever on the APP core, sending the changed parts of each new frame
+fn display_task(mut display: Display) {
+    let delay = Delay::new();
+    let mut mirror = PanelMirror::new();
+    let mut display_on = true;
+    let mut contrast = DEFAULT_CONTRAST;
+    let mut inverted = false;
+
+    loop {
+        let on = DISPLAY_ON.load(Ordering::Relaxed);
+        if on != display_on && display.set_display_on(on).is_ok() {
+            display_on = on;
+        }
+        let wanted = CONTRAST.load(Ordering::Relaxed);
+        if wanted != contrast && display.set_contrast(wanted).is_ok() {
+            contrast = wanted;
+        }
+        let invert = INVERTED.load(Ordering::Relaxed);
+        if invert != inverted && display.set_inverted(invert).is_ok() {
+            inverted = invert;
+        }
+
+        // Settings above are applied first, so a panel switched off stays off while held
+        if HOLD_REQUESTED.load(Ordering::Acquire) {
+            HELD.store(true, Ordering::Release);
+            while HOLD_REQUESTED.load(Ordering::Acquire) {}
+            HELD.store(false, Ordering::Release);
+            continue;
+        }
+
+        let Some(frame) = critical_section::with(|cs| PENDING_FRAME.borrow_ref_mut(cs).take())
+        else {
+            delay.delay_micros(IDLE_POLL_MICROS);
+            continue;
+        };
+
+        let transfer_start = Instant::now();
+        let mut sent = 0;
+        let mut failed = false;
+        for span in mirror.dirty_spans(&frame) {
+            if display
+                .write_span(span.page, span.start, frame.span_bytes(&span))
+                .is_err()
+            {
+                failed = true;
+                break;
+            }
+            sent += span.len();
+        }
+
+        // A transfer can be cut short, e.g. while flash writes park this core, after
+        // which the panel contents are unknown until everything is sent again
+        if failed {
+            mirror.invalidate();
+        } else {
+            mirror.mark_shown(&frame);
+        }
+
+        LAST_TRANSFER_MICROS.store(
+            transfer_start.elapsed().as_micros() as u32,
+            Ordering::Relaxed,
+        );
+        FRAMES_SENT.fetch_add(1, Ordering::Relaxed);
+        BYTES_SENT.fetch_add(sent as u32, Ordering::Relaxed);
+    }
+}
@@ -1,49 +1,176 @@
 use embedded_storage::{ReadStorage, Storage};
 use esp_storage::{FlashStorage, FlashStorageError};
 
-/// Flash address offset where we store the high score
+use crate::crash::{CRASH_LOG_LEN, CrashLog};
+use crate::joystick::Calibration;
+use crate::leaderboard::Leaderboard;
+use crate::settings::Settings;
+
+/// Flash address offset where we store the leaderboard
 /// This is in the NVS-like area, far from program code
 const HIGH_SCORE_ADDR: u32 = 0x9000;
 
-/// Magic number to verify the high score data is valid
+/// Magic number of the single high score stored by earlier firmware
 const MAGIC: u32 = 0xDEADBEEF;
 
-/// Structure stored in flash
-#[repr(C)]
-struct HighScoreData {
-    magic: u32,
-    score: u32,
-}
+/// Magic number to verify the leaderboard data is valid
+const LEADERBOARD_MAGIC: u32 = 0x544F5035; // "TOP5"
+
+/// Flash address of the joystick calibration, one sector after the high score
+const CALIBRATION_ADDR: u32 = 0xA000;
+
+/// Magic number to verify the calibration data is valid
+const CALIBRATION_MAGIC: u32 = 0x4A4F5931; // "JOY1"
+
+/// Flash address of the user settings
+const SETTINGS_ADDR: u32 = 0xB000;
+
+/// Magic number to verify the settings data is valid.
+/// Change this whenever the settings layout changes so old data falls back to defaults.
+const SETTINGS_MAGIC: u32 = 0x53455437; // "SET7"
 
-/// Load the high score from flash storage
-/// Returns 0 if no valid high score is found
-pub fn load_high_score(flash: &mut FlashStorage) -> u32 {
-    let mut buffer = [0u8; 8]; // 4 bytes for magic + 4 bytes for score
+/// Flash address of the description of the last panic
+const CRASH_LOG_ADDR: u32 = 0xC000;
 
-    match flash.read(HIGH_SCORE_ADDR, &mut buffer) {
-        Ok(_) => {
-            let magic = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
-            let score = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]);
+/// Magic number to verify the crash log is valid
+const CRASH_LOG_MAGIC: u32 = 0x43525348; // "CRSH"
 
-            if magic == MAGIC { score } else { 0 }
+/// Load the leaderboard from flash storage
+/// A high score saved by earlier firmware becomes the only entry, returns an empty board
+/// if nothing valid is found
+pub fn load_leaderboard(flash: &mut FlashStorage) -> Leaderboard {
+    let mut buffer = [0u8; 4 + Leaderboard::SIZE];
+    if flash.read(HIGH_SCORE_ADDR, &mut buffer).is_err() {
+        return Leaderboard::new();
+    }
+
+    let magic = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+    match magic {
+        LEADERBOARD_MAGIC => {
+            let mut bytes = [0u8; Leaderboard::SIZE];
+            bytes.copy_from_slice(&buffer[4..]);
+            Leaderboard::from_bytes(&bytes).unwrap_or_default()
+        }
+        MAGIC => {
+            let mut leaderboard = Leaderboard::new();
+            leaderboard.insert(u32::from_le_bytes([
+                buffer[4], buffer[5], buffer[6], buffer[7],
+            ]));
+            leaderboard
         }
-        Err(_) => 0,
+        _ => Leaderboard::new(),
     }
 }
 
-/// Save the high score to flash storage
-pub fn save_high_score(score: u32, flash: &mut FlashStorage) -> Result<(), FlashStorageError> {
-    let data = HighScoreData {
-        magic: MAGIC,
-        score,
-    };
-
-    let mut buffer = [0u8; 8];
-    buffer[0..4].copy_from_slice(&data.magic.to_le_bytes());
-    buffer[4..8].copy_from_slice(&data.score.to_le_bytes());
+/// Save the leaderboard to flash storage
+pub fn save_leaderboard(
+    leaderboard: &Leaderboard,
+    flash: &mut FlashStorage,
+) -> Result<(), FlashStorageError> {
+    let mut buffer = [0u8; 4 + Leaderboard::SIZE];
+    buffer[0..4].copy_from_slice(&LEADERBOARD_MAGIC.to_le_bytes());
+    buffer[4..].copy_from_slice(&leaderboard.to_bytes());
 
     // Write the data (esp-storage handles erase internally)
     flash.write(HIGH_SCORE_ADDR, &buffer)?;
 
     Ok(())
 }
+
+/// Load the joystick calibration from flash storage
+/// Returns None if the stick has never been calibrated
+pub fn load_calibration(flash: &mut FlashStorage) -> Option<Calibration> {
+    let mut buffer = [0u8; 8]; // 4 bytes for magic + 2 bytes center + 2 bytes deadzone
+
+    flash.read(CALIBRATION_ADDR, &mut buffer).ok()?;
+    let magic = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+    if magic != CALIBRATION_MAGIC {
+        return None;
+    }
+
+    Some(Calibration {
+        center: u16::from_le_bytes([buffer[4], buffer[5]]),
+        deadzone: u16::from_le_bytes([buffer[6], buffer[7]]),
+    })
+}
+
+/// Save the joystick calibration to flash storage
+pub fn save_calibration(
+    calibration: &Calibration,
+    flash: &mut FlashStorage,
+) -> Result<(), FlashStorageError> {
+    let mut buffer = [0u8; 8];
+    buffer[0..4].copy_from_slice(&CALIBRATION_MAGIC.to_le_bytes());
+    buffer[4..6].copy_from_slice(&calibration.center.to_le_bytes());
+    buffer[6..8].copy_from_slice(&calibration.deadzone.to_le_bytes());
+
+    flash.write(CALIBRATION_ADDR, &buffer)?;
+
+    Ok(())
+}
+
+/// Load the user settings from flash storage
+/// Returns None if no valid settings are found
+pub fn load_settings(flash: &mut FlashStorage) -> Option<Settings> {
+    let mut buffer = [0u8; 4 + Settings::SIZE];
+
+    flash.read(SETTINGS_ADDR, &mut buffer).ok()?;
+    let magic = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+    if magic != SETTINGS_MAGIC {
+        return None;
+    }
+
+    let mut bytes = [0u8; Settings::SIZE];
+    bytes.copy_from_slice(&buffer[4..]);
+    Settings::from_bytes(&bytes)
+}
+
+/// Save the user settings to flash storage
+pub fn save_settings(
+    settings: &Settings,
+    flash: &mut FlashStorage,
+) -> Result<(), FlashStorageError> {
+    let mut buffer = [0u8; 4 + Settings::SIZE];
+    buffer[0..4].copy_from_slice(&SETTINGS_MAGIC.to_le_bytes());
+    buffer[4..].copy_from_slice(&settings.to_bytes());
+
+    flash.write(SETTINGS_ADDR, &buffer)?;
+
+    Ok(())
+}
+
+/// Load the description of the last panic from flash storage
+/// Returns None if there was no panic since the log was last cleared
+pub fn load_crash_log(flash: &mut FlashStorage) -> Option<CrashLog> {
+    let mut buffer = [0u8; 8 + CRASH_LOG_LEN]; // 4 bytes magic + 4 bytes length + text
+
+    flash.read(CRASH_LOG_ADDR, &mut buffer).ok()?;
+    let magic = u32::from_le_bytes([buffer[0], buffer[1], buffer[2], buffer[3]]);
+    if magic != CRASH_LOG_MAGIC {
+        return None;
+    }
+
+    let len = u32::from_le_bytes([buffer[4], buffer[5], buffer[6], buffer[7]]) as usize;
+    let text = core::str::from_utf8(buffer[8..].get(..len)?).ok()?;
+    CrashLog::try_from(text).ok()
+}
+
+/// Save the description of a panic to flash storage
+pub fn save_crash_log(log: &str, flash: &mut FlashStorage) -> Result<(), FlashStorageError> {
+    let len = log.len().min(CRASH_LOG_LEN);
+    let mut buffer = [0u8; 8 + CRASH_LOG_LEN];
+    buffer[0..4].copy_from_slice(&CRASH_LOG_MAGIC.to_le_bytes());
+    buffer[4..8].copy_from_slice(&(len as u32).to_le_bytes());
+    buffer[8..8 + len].copy_from_slice(&log.as_bytes()[..len]);
+
+    flash.write(CRASH_LOG_ADDR, &buffer)?;
+
+    Ok(())
+}
+
+/// Forget the last panic
+pub fn clear_crash_log(flash: &mut FlashStorage) -> Result<(), FlashStorageError> {
+    flash.write(CRASH_LOG_ADDR, &[0u8; 4])?;
+
+    Ok(())
+}